@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS `visits` (
+  `visit_id` bigint not null auto_increment primary key,
+  `link_id` varchar(255) not null,
+  `visited_at` timestamp not null default current_timestamp,
+  `referrer` text,
+  `user_agent` text,
+  `client_ip` varchar(45),
+  index `visits_link_id_visited_at` (`link_id`, `visited_at`),
+  constraint `visits_link_id_fk` foreign key (`link_id`) references `links` (`link_id`) on update cascade on delete cascade
+) ENGINE=InnoDB;
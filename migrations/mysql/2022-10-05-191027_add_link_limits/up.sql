@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE `links` ADD COLUMN `expires_at` timestamp null default null, ADD COLUMN `max_visits` integer null default null;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE `links` DROP COLUMN `expired_at`;
\ No newline at end of file
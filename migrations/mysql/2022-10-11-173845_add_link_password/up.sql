@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE `links` ADD COLUMN `password` varchar(255) null default null;
\ No newline at end of file
@@ -0,0 +1,18 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS `users` (
+  `user_id` bigint not null auto_increment primary key,
+  `username` varchar(255) not null unique,
+  `created_at` timestamp not null default current_timestamp
+) ENGINE=InnoDB;
+
+CREATE TABLE IF NOT EXISTS `api_keys` (
+  `key_id` varchar(32) not null primary key,
+  `user_id` bigint not null,
+  `key_hash` varchar(255) not null,
+  `created_at` timestamp not null default current_timestamp,
+  constraint `api_keys_user_id_fk` foreign key (`user_id`) references `users` (`user_id`) on delete cascade
+) ENGINE=InnoDB;
+
+ALTER TABLE `links`
+  ADD COLUMN `owner_id` bigint null default null,
+  ADD CONSTRAINT `links_owner_id_fk` FOREIGN KEY (`owner_id`) REFERENCES `users` (`user_id`) ON DELETE SET NULL;
\ No newline at end of file
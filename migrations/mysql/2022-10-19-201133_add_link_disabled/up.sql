@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE `links` ADD COLUMN `disabled` boolean not null default false;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX `links_target_host_index` ON `links`;
+ALTER TABLE `links` DROP COLUMN `target_host`;
\ No newline at end of file
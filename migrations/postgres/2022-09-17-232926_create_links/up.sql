@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS links (
+  link_id varchar(255) not null primary key,
+  target text not null,
+  control_key varchar(255) not null,
+  added_at timestamp not null default timezone('utc', now()),
+  visit_count integer not null default 0
+);
\ No newline at end of file
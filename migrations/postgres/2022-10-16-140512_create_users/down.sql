@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE links DROP COLUMN owner_id;
+DROP TABLE api_keys;
+DROP TABLE users;
\ No newline at end of file
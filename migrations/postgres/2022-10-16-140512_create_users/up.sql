@@ -0,0 +1,15 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS users (
+  user_id bigserial primary key,
+  username varchar(255) not null unique,
+  created_at timestamp not null default timezone('utc', now())
+);
+
+CREATE TABLE IF NOT EXISTS api_keys (
+  key_id varchar(32) not null primary key,
+  user_id bigint not null references users (user_id) on delete cascade,
+  key_hash varchar(255) not null,
+  created_at timestamp not null default timezone('utc', now())
+);
+
+ALTER TABLE links ADD COLUMN owner_id bigint null default null references users (user_id) on delete set null;
\ No newline at end of file
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+ALTER TABLE links ADD COLUMN target_host varchar(255) null default null;
+
+UPDATE links SET target_host = substr(target, strpos(target, '://') + 3) WHERE strpos(target, '://') > 0 AND strpos(target, '://') = strpos(target, ':');
+UPDATE links SET target_host = substr(target_host, 1, strpos(target_host, '/') - 1) WHERE strpos(target_host, '/') > 0;
+UPDATE links SET target_host = substr(target_host, 1, strpos(target_host, '?') - 1) WHERE strpos(target_host, '?') > 0;
+UPDATE links SET target_host = substr(target_host, 1, strpos(target_host, '#') - 1) WHERE strpos(target_host, '#') > 0;
+UPDATE links SET target_host = substr(target_host, strpos(target_host, '@') + 1) WHERE strpos(target_host, '@') > 0;
+UPDATE links SET target_host = substr(target_host, 1, strpos(target_host, ']')) WHERE target_host LIKE '[%';
+UPDATE links SET target_host = substr(target_host, 1, strpos(target_host, ':') - 1) WHERE target_host NOT LIKE '[%' AND strpos(target_host, ':') > 0;
+UPDATE links SET target_host = NULL WHERE target_host = '';
+UPDATE links SET target_host = lower(target_host);
+
+CREATE INDEX links_target_host_index ON links (target_host);
\ No newline at end of file
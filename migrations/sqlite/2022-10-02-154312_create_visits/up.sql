@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS visits (
+  visit_id integer not null primary key autoincrement,
+  link_id varchar(255) not null references links (link_id) on update cascade on delete cascade,
+  visited_at timestamp not null default current_timestamp,
+  referrer text,
+  user_agent text,
+  client_ip varchar(45)
+);
+
+CREATE INDEX visits_link_id_visited_at ON visits (link_id, visited_at);
\ No newline at end of file
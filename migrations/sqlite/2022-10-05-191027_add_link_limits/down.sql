@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE links DROP COLUMN max_visits;
+ALTER TABLE links DROP COLUMN expires_at;
\ No newline at end of file
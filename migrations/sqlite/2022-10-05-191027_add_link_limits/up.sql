@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE links ADD COLUMN expires_at timestamp null default null;
+ALTER TABLE links ADD COLUMN max_visits integer null default null;
\ No newline at end of file
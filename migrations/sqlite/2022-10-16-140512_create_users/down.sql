@@ -0,0 +1,27 @@
+-- This file should undo anything in `up.sql`
+CREATE TEMPORARY TABLE visits_backup AS SELECT * FROM visits;
+
+CREATE TABLE links_new (
+  link_id varchar(255) not null primary key,
+  target text not null,
+  control_key varchar(255) not null,
+  added_at timestamp not null default current_timestamp,
+  visit_count integer not null default 0,
+  expires_at timestamp null default null,
+  max_visits integer null default null,
+  expired_at timestamp null default null,
+  password varchar(255) null default null
+);
+
+INSERT INTO links_new (link_id, target, control_key, added_at, visit_count, expires_at, max_visits, expired_at, password)
+  SELECT link_id, target, control_key, added_at, visit_count, expires_at, max_visits, expired_at, password FROM links;
+
+DROP TABLE links;
+ALTER TABLE links_new RENAME TO links;
+
+DELETE FROM visits;
+INSERT INTO visits SELECT * FROM visits_backup;
+DROP TABLE visits_backup;
+
+DROP TABLE api_keys;
+DROP TABLE users;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE links DROP COLUMN disabled;
\ No newline at end of file
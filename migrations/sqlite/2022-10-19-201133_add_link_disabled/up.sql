@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE links ADD COLUMN disabled boolean not null default 0;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX links_link_key_index;
+ALTER TABLE links DROP COLUMN link_key;
\ No newline at end of file
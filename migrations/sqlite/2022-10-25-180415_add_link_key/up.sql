@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE links ADD COLUMN link_key varchar(255) not null default '';
+
+UPDATE links SET link_key = link_id;
+
+CREATE UNIQUE INDEX links_link_key_index ON links (link_key);
\ No newline at end of file
@@ -0,0 +1,12 @@
+use rocket::{catch, http::Status, serde::json::Json};
+use crate::responses::{ResponseErrorType, ResponseData, Response};
+
+#[catch(401)]
+pub fn unauthorized() -> (Status, Json<Response<()>>) {
+  ResponseData::<()>::new()
+    .set_status(Status::Unauthorized)
+    .set_error_type(ResponseErrorType::InvalidAdminTokenError)
+    .set_error_message(String::from("Valid admin token has to be provided in 'Authorization' header!"))
+    .to_response()
+    .json_respond()
+}
\ No newline at end of file
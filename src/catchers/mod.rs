@@ -1 +1,2 @@
-pub mod root;
\ No newline at end of file
+pub mod root;
+pub mod admin;
\ No newline at end of file
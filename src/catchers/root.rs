@@ -1,7 +1,5 @@
-use chrono::Utc;
 use rocket::{
   catch, 
-  State, 
   Request, 
   http::{
     Header, 
@@ -10,12 +8,12 @@ use rocket::{
   Responder, 
   serde::json::{
     Json
-  },
-  outcome::Outcome::Success
+  }
 };
 use crate::responses::{ResponseErrorType, ResponseData, Response, errors};
-use crate::fairings::rate_limit::RateLimitState;
-use crate::config;
+use crate::fairings::rate_limit::RateLimitSlot;
+
+type JsonResponse = (Status, Json<Response<()>>);
 
 #[derive(Responder)]
 pub struct RateLimitedWrappingResponder<'h, R> {
@@ -24,7 +22,7 @@ pub struct RateLimitedWrappingResponder<'h, R> {
 }
 
 #[catch(429)]
-pub async fn rate_limited<'a>(req: &Request<'_>) -> Result<RateLimitedWrappingResponder<'a, (Status, Json<Response<()>>)>, (Status, Json<Response<()>>)> {
+pub fn rate_limited<'a>(req: &Request<'_>) -> Result<RateLimitedWrappingResponder<'a, JsonResponse>, JsonResponse> {
   let mut response_data: ResponseData<()> = ResponseData::new();
 
   response_data = response_data
@@ -32,54 +30,42 @@ pub async fn rate_limited<'a>(req: &Request<'_>) -> Result<RateLimitedWrappingRe
     .set_error_type(ResponseErrorType::UndefinedError)
     .set_error_message(String::from("You have been rate limited but server could not determine the length of your cooldown. Please wait for at least an hour and try again!"));
 
-  match req.guard::<&State<config::Config>>().await {
-    Success(config) => match req.client_ip() {
-      Some(ip) => match req.guard::<&State<RateLimitState>>().await {
-        Success(state) => match state.lock() {
-            Ok(lock) => match lock.get(&ip) {
-              Some(entry) => {
-                let max_requests = config.max_requests.clone();
-                let time_window = config.max_requests_time_window.clone();
-                let date = entry.1.clone();
-                let retry_after = time_window - Utc::now().naive_utc().signed_duration_since(date).num_seconds();
+  match req.local_cache(RateLimitSlot::default).get() {
+    Some(decision) => {
+      let max_requests = decision.limit;
+      let time_window = decision.time_window;
+      let retry_after = decision.retry_after;
 
-                let response_data = response_data
-                  .set_status(Status::TooManyRequests)
-                  .set_error_type(ResponseErrorType::RateLimitedError)
-                  .set_error_message(String::from("You have been rate limited!"))
-                  .set_error_data(errors::Errors::RateLimitedError {
-                    max_requests: max_requests,
-                    time_window: time_window,
-                    cooldown: retry_after
-                  });
+      let response_data = response_data
+        .set_status(Status::TooManyRequests)
+        .set_error_type(ResponseErrorType::RateLimitedError)
+        .set_error_message(String::from("You have been rate limited!"))
+        .set_error_data(errors::Errors::RateLimitedError {
+          max_requests: max_requests,
+          time_window: time_window,
+          cooldown: retry_after
+        });
 
-                let response = RateLimitedWrappingResponder {
-                  inner: response_data.to_response().json_respond(),
-                  retry_after: Header::new("Retry-After", format!("{}", retry_after))
-                };
+      let response = RateLimitedWrappingResponder {
+        inner: response_data.to_response().json_respond(),
+        retry_after: Header::new("Retry-After", format!("{}", retry_after))
+      };
 
-                Ok(response)
-              },
-              None => Err(response_data.to_response().json_respond())
-            },
-            Err(_) => {
-              Err(response_data.to_response().json_respond())
-            } 
-        },
-        _ => {
-          Err(response_data.to_response().json_respond())
-        }
-      },
-      None => {
-        Err(response_data.to_response().json_respond())
-      }
+      Ok(response)
     },
-    _ => {
+    None => {
       Err(response_data.to_response().json_respond())
     }
   }
 }
 
+#[catch(401)]
+pub fn unauthorized() -> (Status, Json<Response<()>>) {
+  errors::Errors::invalid_api_key(ResponseData::<()>::new())
+    .to_response()
+    .json_respond()
+}
+
 #[catch(422)]
 pub fn invalid_request_data() -> (Status, Json<Response<()>>) {
   ResponseData::<()>::new()
@@ -1,12 +1,130 @@
 pub mod database {
   use diesel::r2d2;
   use diesel::r2d2::ConnectionManager;
-  use diesel::mysql::MysqlConnection;
+  use diesel::Connection;
+  use diesel::migration::MigrationSource;
+  use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
   use crate::config;
+  use std::sync::Arc;
+  use crate::store::{Store, database::DatabaseLinkStore, cache::{LinkCache, CachedLinkStore}, normalized::NormalizedLinkStore};
   use rocket::{Rocket, Build, fairing::{self, Fairing}};
 
-  pub type Pool = r2d2::Pool<ConnectionManager<MysqlConnection>>;
-  pub type PoolConnection = r2d2::PooledConnection<ConnectionManager<MysqlConnection>>;
+  #[cfg(not(any(feature = "mysql", feature = "postgres", feature = "sqlite")))]
+  compile_error!("One of \"mysql\", \"postgres\" or \"sqlite\" features has to be enabled!");
+  #[cfg(any(all(feature = "mysql", feature = "postgres"), all(feature = "mysql", feature = "sqlite"), all(feature = "postgres", feature = "sqlite")))]
+  compile_error!("Only one of \"mysql\", \"postgres\" and \"sqlite\" features can be enabled at once! Use `--no-default-features` when selecting a backend other than MySQL.");
+
+  #[cfg(feature = "mysql")]
+  pub type DbConnection = diesel::mysql::MysqlConnection;
+  #[cfg(feature = "mysql")]
+  pub type DbBackend = diesel::mysql::Mysql;
+  #[cfg(feature = "mysql")]
+  pub const BACKEND_NAME: &str = "MySQL";
+
+  #[cfg(feature = "postgres")]
+  pub type DbConnection = diesel::pg::PgConnection;
+  #[cfg(feature = "postgres")]
+  pub type DbBackend = diesel::pg::Pg;
+  #[cfg(feature = "postgres")]
+  pub const BACKEND_NAME: &str = "PostgreSQL";
+
+  #[cfg(feature = "sqlite")]
+  pub type DbConnection = diesel::sqlite::SqliteConnection;
+  #[cfg(feature = "sqlite")]
+  pub type DbBackend = diesel::sqlite::Sqlite;
+  #[cfg(feature = "sqlite")]
+  pub const BACKEND_NAME: &str = "SQLite";
+
+  #[cfg(feature = "mysql")]
+  pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/mysql");
+  #[cfg(feature = "postgres")]
+  pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/postgres");
+  #[cfg(feature = "sqlite")]
+  pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/sqlite");
+
+  pub type Pool = r2d2::Pool<ConnectionManager<DbConnection>>;
+  pub type PoolConnection = r2d2::PooledConnection<ConnectionManager<DbConnection>>;
+
+  #[cfg(feature = "mysql")]
+  pub fn connection_url(database_url: &str) -> Option<String> {
+    match database_url.starts_with("mysql://") {
+      true => Some(database_url.to_string()),
+      false => None
+    }
+  }
+
+  #[cfg(feature = "postgres")]
+  pub fn connection_url(database_url: &str) -> Option<String> {
+    match database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+      true => Some(database_url.to_string()),
+      false => None
+    }
+  }
+
+  #[cfg(feature = "sqlite")]
+  pub fn connection_url(database_url: &str) -> Option<String> {
+    database_url.strip_prefix("sqlite://").map(String::from)
+  }
+
+  #[cfg(feature = "sqlite")]
+  #[derive(Debug)]
+  struct SqliteCustomizer;
+
+  #[cfg(feature = "sqlite")]
+  impl r2d2::CustomizeConnection<DbConnection, r2d2::Error> for SqliteCustomizer {
+    fn on_acquire(&self, conn: &mut DbConnection) -> Result<(), r2d2::Error> {
+      use diesel::connection::SimpleConnection;
+
+      conn.batch_execute("PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000;")
+        .map_err(r2d2::Error::QueryError)
+    }
+  }
+
+  pub fn run_migrations(conn: &mut DbConnection) -> Result<usize, String> {
+    let embedded = match MigrationSource::<DbBackend>::migrations(&MIGRATIONS) {
+      Ok(migrations) => migrations.iter()
+        .map(|migration| migration.name().version().as_owned())
+        .collect::<Vec<_>>(),
+      Err(e) => return Err(format!("Could not load embedded migrations: {}", e))
+    };
+
+    match conn.applied_migrations() {
+      Ok(applied) => {
+        if let Some(unknown) = applied.iter().find(|version| !embedded.contains(*version)) {
+          return Err(format!("Database schema is newer than this binary (unknown migration '{}' has been applied)! Update the API before starting it.", unknown));
+        }
+      },
+      Err(e) => return Err(format!("Could not fetch applied migrations: {}", e))
+    }
+
+    match conn.run_pending_migrations(MIGRATIONS) {
+      Ok(applied) => Ok(applied.len()),
+      Err(e) => Err(format!("Could not apply pending migrations: {}", e))
+    }
+  }
+
+  #[cfg(all(test, feature = "sqlite"))]
+  pub fn test_pool() -> Pool {
+    let pool = r2d2::Pool::builder()
+      .max_size(1)
+      .connection_customizer(Box::new(SqliteCustomizer))
+      .build(ConnectionManager::<DbConnection>::new(":memory:"))
+      .unwrap();
+
+    run_migrations(&mut pool.get().unwrap()).unwrap();
+
+    pool
+  }
+
+  pub fn migrate(database_url: &str) -> Result<usize, String> {
+    match connection_url(database_url) {
+      Some(database_url) => match DbConnection::establish(&database_url) {
+        Ok(mut conn) => run_migrations(&mut conn),
+        Err(e) => Err(format!("Could not connect to database: {}", e))
+      },
+      None => Err(format!("Database URL '{}' does not match {} backend this binary has been built with!", database_url, BACKEND_NAME))
+    }
+  }
 
   pub struct DatabaseInitiator;
 
@@ -21,21 +139,53 @@ pub mod database {
 
     async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
       match rocket.state::<config::Config>() {
-        Some(config) => {
-          let database_url = config.database_url.clone();
+        Some(config) => match connection_url(&config.database_url) {
+          Some(database_url) => {
+            let manager = ConnectionManager::<DbConnection>::new(&database_url);
+            let builder = r2d2::Pool::builder();
 
-          let manager = ConnectionManager::<MysqlConnection>::new(&database_url);
-          match r2d2::Pool::builder().build(manager) {
-            Ok(pool) => {
-              println!("Database pool initialized!");
+            #[cfg(feature = "sqlite")]
+            let builder = builder.connection_customizer(Box::new(SqliteCustomizer));
 
-              fairing::Result::Ok(rocket.manage(pool))
-            },
-            Err(_) => {
-              println!("Could not initialze database pool!");
-              fairing::Result::Err(rocket)
+            match builder.build(manager) {
+              Ok(pool) => {
+                println!("Database pool initialized ({})!", BACKEND_NAME);
+
+                if config.run_migrations {
+                  let migrated = match pool.get() {
+                    Ok(mut conn) => run_migrations(&mut conn),
+                    Err(_) => Err(String::from("Could not get database pool!"))
+                  };
+
+                  match migrated {
+                    Ok(count) => {
+                      println!("Applied {} pending migration(s).", count);
+                    },
+                    Err(message) => {
+                      println!("{}", message);
+                      return fairing::Result::Err(rocket);
+                    }
+                  }
+                }
+
+                let cache = Arc::new(LinkCache::new(config.link_cache_size, config.link_cache_ttl));
+                let store: Store = Box::new(NormalizedLinkStore::new(
+                  Box::new(CachedLinkStore::new(Box::new(DatabaseLinkStore::new(pool.clone())), cache.clone())),
+                  config.case_insensitive_ids
+                ));
+
+                fairing::Result::Ok(rocket.manage(pool).manage(store).manage(cache))
+              },
+              Err(_) => {
+                println!("Could not initialze database pool!");
+                fairing::Result::Err(rocket)
+              }
             }
-          }          
+          },
+          None => {
+            println!("Database URL '{}' does not match {} backend this binary has been built with!", config.database_url, BACKEND_NAME);
+            fairing::Result::Err(rocket)
+          }
         },
         None => fairing::Result::Err(rocket)
       }
@@ -44,15 +194,468 @@ pub mod database {
 }
 
 pub mod rate_limit {
-  use std::{collections::HashMap, net::IpAddr};
-  use chrono::{NaiveDateTime, Utc};
+  use std::{collections::{BTreeMap, HashMap}, net::IpAddr};
+  use chrono::{DateTime, NaiveDateTime, Utc};
   use rocket::fairing::{self, Fairing, Result};
-  use rocket::{Rocket, Build, Request, Data, State};
-  use rocket::outcome::Outcome;
-  use std::sync::Mutex;
-  use crate::config;
+  use rocket::{Rocket, Build, Orbit, Request, Response};
+  use rocket::http::{Header, Status};
+  use rocket::tokio::{self, time};
+  use std::sync::{Arc, Mutex};
+  use crate::config::{self, RateLimitPolicy};
+  use crate::utils::Cidr;
+
+  #[derive(Debug, Clone, Copy)]
+  pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: i64,
+    pub time_window: i64,
+    pub remaining: i64,
+    pub reset_in: i64,
+    pub retry_after: i64
+  }
+
+  #[cfg(feature = "redis")]
+  impl RateLimitDecision {
+    fn from_log(allowed: bool, policy: &RateLimitPolicy, requests: i64, oldest: Option<i64>, now: i64) -> Self {
+      let limit = policy.max_requests;
+      let window = policy.time_window * 1000;
+      let reset_in = match oldest {
+        Some(oldest) => ((oldest + window - now).max(0) + 999) / 1000,
+        None => 0
+      };
+
+      RateLimitDecision {
+        allowed: allowed,
+        limit: limit,
+        time_window: policy.time_window,
+        remaining: (limit - requests).max(0),
+        reset_in: reset_in,
+        retry_after: match allowed {
+          true => 0,
+          false => reset_in
+        }
+      }
+    }
+  }
+
+  #[derive(Default)]
+  pub struct RateLimitSlot(Mutex<Option<RateLimitDecision>>);
+
+  impl RateLimitSlot {
+    pub fn set(&self, decision: RateLimitDecision) {
+      if let Ok(mut slot) = self.0.lock() {
+        *slot = Some(decision);
+      }
+    }
+
+    pub fn get(&self) -> Option<RateLimitDecision> {
+      match self.0.lock() {
+        Ok(slot) => *slot,
+        Err(_) => None
+      }
+    }
+  }
+
+  #[derive(Debug, Clone)]
+  pub struct ClientUsage {
+    pub client: String,
+    pub policy: String,
+    pub requests: i64,
+    pub reset_at: NaiveDateTime
+  }
+
+  pub trait RateLimitBackend: Send + Sync {
+    fn hit(&self, policy_name: &str, policy: &RateLimitPolicy, client: &IpAddr, cost: i64) -> std::result::Result<RateLimitDecision, String>;
+    fn peek(&self, policy_name: &str, policy: &RateLimitPolicy, client: &IpAddr) -> std::result::Result<RateLimitDecision, String>;
+    fn entries(&self, config: &config::Config) -> std::result::Result<Vec<ClientUsage>, String>;
+    fn sweep(&self, config: &config::Config) -> std::result::Result<usize, String>;
+  }
+
+  pub type RateLimitState = Arc<dyn RateLimitBackend>;
+
+  pub struct RateLimitAllowlist(pub Vec<Cidr>);
+
+  impl RateLimitAllowlist {
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+      self.0.iter().any(|cidr| cidr.contains(ip))
+    }
+  }
+
+  fn to_datetime(millis: i64) -> NaiveDateTime {
+    DateTime::from_timestamp_millis(millis)
+      .map(|datetime| datetime.naive_utc())
+      .unwrap_or_default()
+  }
+
+  type ClientKey = (String, IpAddr);
+
+  #[derive(Default, Clone, Copy)]
+  struct WindowCounter {
+    window_start: i64,
+    previous: i64,
+    current: i64
+  }
+
+  impl WindowCounter {
+    fn advance(&mut self, now: i64, window: i64) {
+      let window_start = now - now.rem_euclid(window);
+
+      if window_start != self.window_start {
+        self.previous = match window_start - self.window_start == window {
+          true => self.current,
+          false => 0
+        };
+        self.current = 0;
+        self.window_start = window_start;
+      }
+    }
+
+    fn requests(&self, now: i64, window: i64) -> i64 {
+      let remaining = window - (now - self.window_start);
+
+      (self.previous * remaining + window - 1) / window + self.current
+    }
+
+    fn reset_in(&self, now: i64, window: i64) -> i64 {
+      match (self.previous, self.current) {
+        (_, current) if current > 0 => self.window_start + 2 * window - now,
+        (previous, _) if previous > 0 => self.window_start + window - now,
+        _ => 0
+      }
+    }
+
+    fn retry_after(&self, now: i64, window: i64, policy: &RateLimitPolicy, cost: i64) -> i64 {
+      let elapsed = now - self.window_start;
+
+      if self.current + cost <= policy.max_requests {
+        match self.previous {
+          0 => 0,
+          previous => (window - (policy.max_requests - self.current - cost) * window / previous - elapsed).max(0)
+        }
+      } else {
+        match self.current {
+          0 => window - elapsed,
+          current => window - elapsed + (window - (policy.max_requests - cost).max(0) * window / current).max(0)
+        }
+      }
+    }
+
+    fn decision(&self, allowed: bool, policy: &RateLimitPolicy, cost: i64, now: i64) -> RateLimitDecision {
+      let window = policy.time_window * 1000;
+
+      RateLimitDecision {
+        allowed: allowed,
+        limit: policy.max_requests,
+        time_window: policy.time_window,
+        remaining: (policy.max_requests - self.requests(now, window)).max(0),
+        reset_in: (self.reset_in(now, window) + 999) / 1000,
+        retry_after: match allowed {
+          true => 0,
+          false => (self.retry_after(now, window, policy, cost) + 999) / 1000
+        }
+      }
+    }
+  }
+
+  #[derive(Default)]
+  struct MemoryState {
+    counters: HashMap<ClientKey, (u64, WindowCounter)>,
+    last_hits: BTreeMap<u64, ClientKey>,
+    hits: u64
+  }
+
+  impl MemoryState {
+    fn touch(&mut self, key: ClientKey) -> &mut WindowCounter {
+      self.hits += 1;
+
+      let hit = self.hits;
+      let entry = self.counters.entry(key.clone()).or_insert_with(|| (hit, WindowCounter::default()));
+
+      self.last_hits.remove(&entry.0);
+      self.last_hits.insert(hit, key);
+      entry.0 = hit;
+
+      &mut entry.1
+    }
+
+    fn evict_least_recent(&mut self) {
+      let least_recent = self.last_hits.keys().next().copied();
+
+      if let Some(key) = least_recent.and_then(|hit| self.last_hits.remove(&hit)) {
+        self.counters.remove(&key);
+      }
+    }
+  }
+
+  pub struct MemoryBackend {
+    state: Mutex<MemoryState>,
+    max_clients: usize
+  }
+
+  impl MemoryBackend {
+    pub fn new(max_clients: usize) -> Self {
+      MemoryBackend {
+        state: Mutex::new(MemoryState::default()),
+        max_clients: max_clients.max(1)
+      }
+    }
+  }
+
+  impl RateLimitBackend for MemoryBackend {
+    fn hit(&self, policy_name: &str, policy: &RateLimitPolicy, client: &IpAddr, cost: i64) -> std::result::Result<RateLimitDecision, String> {
+      let now = Utc::now().timestamp_millis();
+      let window = policy.time_window * 1000;
+
+      match self.state.lock() {
+        Ok(mut lock) => {
+          let key = (policy_name.to_string(), *client);
+
+          if !lock.counters.contains_key(&key) && lock.counters.len() >= self.max_clients {
+            lock.evict_least_recent();
+          }
+
+          let counter = lock.touch(key);
+
+          counter.advance(now, window);
+
+          let allowed = counter.requests(now, window) + cost <= policy.max_requests;
+
+          if allowed {
+            counter.current += cost;
+          }
+
+          Ok(counter.decision(allowed, policy, cost, now))
+        },
+        Err(_) => Err(String::from("Could not acquire lock on rate limit state!"))
+      }
+    }
+
+    fn peek(&self, policy_name: &str, policy: &RateLimitPolicy, client: &IpAddr) -> std::result::Result<RateLimitDecision, String> {
+      let now = Utc::now().timestamp_millis();
+      let window = policy.time_window * 1000;
+
+      match self.state.lock() {
+        Ok(lock) => {
+          let mut counter = lock.counters.get(&(policy_name.to_string(), *client))
+            .map(|(_, counter)| *counter)
+            .unwrap_or_default();
+
+          counter.advance(now, window);
+
+          let allowed = counter.requests(now, window) < policy.max_requests;
+
+          Ok(counter.decision(allowed, policy, 1, now))
+        },
+        Err(_) => Err(String::from("Could not acquire lock on rate limit state!"))
+      }
+    }
+
+    fn entries(&self, config: &config::Config) -> std::result::Result<Vec<ClientUsage>, String> {
+      let now = Utc::now().timestamp_millis();
+
+      match self.state.lock() {
+        Ok(lock) => Ok(
+          lock.counters.iter()
+            .filter_map(|((policy_name, ip), (_, counter))| {
+              let window = config.rate_limit_policy(policy_name)?.time_window * 1000;
+              let mut counter = *counter;
+
+              counter.advance(now, window);
+
+              match counter.requests(now, window) {
+                0 => None,
+                requests => Some(ClientUsage {
+                  client: ip.to_string(),
+                  policy: policy_name.clone(),
+                  requests: requests,
+                  reset_at: to_datetime(now + counter.reset_in(now, window))
+                })
+              }
+            })
+            .collect()
+        ),
+        Err(_) => Err(String::from("Could not acquire lock on rate limit state!"))
+      }
+    }
+
+    fn sweep(&self, config: &config::Config) -> std::result::Result<usize, String> {
+      let now = Utc::now().timestamp_millis();
+
+      match self.state.lock() {
+        Ok(mut lock) => {
+          let state = &mut *lock;
+          let tracked = state.counters.len();
+          let last_hits = &mut state.last_hits;
+
+          state.counters.retain(|(policy_name, _), (hit, counter)| {
+            let active = match config.rate_limit_policy(policy_name) {
+              Some(policy) => {
+                let window = policy.time_window * 1000;
+
+                counter.advance(now, window);
+                counter.requests(now, window) > 0
+              },
+              None => false
+            };
+
+            if !active {
+              last_hits.remove(&*hit);
+            }
+
+            active
+          });
+
+          Ok(tracked - state.counters.len())
+        },
+        Err(_) => Err(String::from("Could not acquire lock on rate limit state!"))
+      }
+    }
+  }
+
+  #[cfg(feature = "redis")]
+  const SLIDING_WINDOW_SCRIPT: &str = r#"
+    redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', ARGV[1] - ARGV[2])
+    local requests = redis.call('ZCARD', KEYS[1])
+    local allowed = 0
+    if requests + tonumber(ARGV[5]) <= tonumber(ARGV[3]) then
+      for i = 1, tonumber(ARGV[5]) do
+        redis.call('ZADD', KEYS[1], ARGV[1], ARGV[4] .. '-' .. i)
+      end
+      requests = requests + tonumber(ARGV[5])
+      allowed = 1
+    end
+    redis.call('PEXPIRE', KEYS[1], ARGV[2])
+    local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+    return {allowed, requests, tonumber(oldest[2] or -1)}
+  "#;
+
+  #[cfg(feature = "redis")]
+  pub struct RedisBackend {
+    pool: r2d2::Pool<redis::Client>,
+    script: redis::Script
+  }
+
+  #[cfg(feature = "redis")]
+  impl RedisBackend {
+    const KEY_PREFIX: &'static str = "link_shortener:rate_limit:";
+
+    pub fn new(redis_url: &str) -> std::result::Result<Self, String> {
+      match redis::Client::open(redis_url) {
+        Ok(client) => match r2d2::Pool::builder().build(client) {
+          Ok(pool) => Ok(RedisBackend {
+            pool: pool,
+            script: redis::Script::new(SLIDING_WINDOW_SCRIPT)
+          }),
+          Err(e) => Err(format!("Could not initialize Redis pool: {}", e))
+        },
+        Err(e) => Err(format!("Redis URL '{}' is not valid: {}", redis_url, e))
+      }
+    }
+  }
+
+  #[cfg(feature = "redis")]
+  impl RateLimitBackend for RedisBackend {
+    fn hit(&self, policy_name: &str, policy: &RateLimitPolicy, client: &IpAddr, cost: i64) -> std::result::Result<RateLimitDecision, String> {
+      let mut conn = self.pool.get()
+        .map_err(|_| String::from("Could not get Redis pool!"))?;
+      let key = format!("{}{}:{}", Self::KEY_PREFIX, policy_name, client);
+      let now = Utc::now().timestamp_millis();
+      let window = policy.time_window * 1000;
+
+      let (allowed, requests, oldest): (i64, i64, i64) = self.script
+        .key(&key)
+        .arg(now)
+        .arg(window)
+        .arg(policy.max_requests)
+        .arg(format!("{}-{}", now, nanoid::nanoid!(8)))
+        .arg(cost)
+        .invoke(&mut *conn)
+        .map_err(|e| format!("Could not update rate limit state in Redis: {}", e))?;
 
-  pub type RateLimitState = Mutex<HashMap<IpAddr, (i64, NaiveDateTime)>>;
+      let oldest = match oldest {
+        -1 => None,
+        oldest => Some(oldest)
+      };
+
+      Ok(RateLimitDecision::from_log(allowed == 1, policy, requests, oldest, now))
+    }
+
+    fn peek(&self, policy_name: &str, policy: &RateLimitPolicy, client: &IpAddr) -> std::result::Result<RateLimitDecision, String> {
+      let mut conn = self.pool.get()
+        .map_err(|_| String::from("Could not get Redis pool!"))?;
+      let key = format!("{}{}:{}", Self::KEY_PREFIX, policy_name, client);
+      let now = Utc::now().timestamp_millis();
+      let min = format!("({}", now - policy.time_window * 1000);
+
+      let (requests, oldest): (i64, Vec<(String, f64)>) = redis::pipe()
+        .zcount(&key, &min, "+inf")
+        .zrangebyscore_limit_withscores(&key, &min, "+inf", 0, 1)
+        .query(&mut *conn)
+        .map_err(|e| format!("Could not read rate limit state from Redis: {}", e))?;
+
+      let oldest = oldest.first().map(|(_, oldest)| *oldest as i64);
+
+      Ok(RateLimitDecision::from_log(requests < policy.max_requests, policy, requests, oldest, now))
+    }
+
+    fn entries(&self, config: &config::Config) -> std::result::Result<Vec<ClientUsage>, String> {
+      use redis::Commands;
+
+      let mut conn = self.pool.get()
+        .map_err(|_| String::from("Could not get Redis pool!"))?;
+      let now = Utc::now().timestamp_millis();
+
+      let keys: Vec<String> = conn.scan_match::<_, String>(format!("{}*", Self::KEY_PREFIX))
+        .map_err(|e| format!("Could not list rate limit state in Redis: {}", e))?
+        .collect();
+
+      let mut entries: Vec<ClientUsage> = Vec::new();
+
+      for key in keys.iter() {
+        let (policy_name, client) = match key.trim_start_matches(Self::KEY_PREFIX).split_once(':') {
+          Some(key) => key,
+          None => continue
+        };
+        let window = match config.rate_limit_policy(policy_name) {
+          Some(policy) => policy.time_window * 1000,
+          None => continue
+        };
+        let min = format!("({}", now - window);
+
+        let (requests, oldest): (i64, Vec<(String, f64)>) = redis::pipe()
+          .zcount(key, &min, "+inf")
+          .zrangebyscore_limit_withscores(key, &min, "+inf", 0, 1)
+          .query(&mut *conn)
+          .map_err(|e| format!("Could not read rate limit state from Redis: {}", e))?;
+
+        if let Some((_, oldest)) = oldest.first() {
+          entries.push(ClientUsage {
+            client: client.to_string(),
+            policy: policy_name.to_string(),
+            requests: requests,
+            reset_at: to_datetime(*oldest as i64 + window)
+          });
+        }
+      }
+
+      Ok(entries)
+    }
+
+    fn sweep(&self, _config: &config::Config) -> std::result::Result<usize, String> {
+      Ok(0)
+    }
+  }
+
+  pub fn backend(config: &config::Config) -> std::result::Result<RateLimitState, String> {
+    match config.rate_limit_backend.as_str() {
+      "memory" => Ok(Arc::new(MemoryBackend::new(config.rate_limit_max_clients))),
+      #[cfg(feature = "redis")]
+      "redis" => Ok(Arc::new(RedisBackend::new(&config.redis_url)?)),
+      #[cfg(not(feature = "redis"))]
+      "redis" => Err(String::from("Redis rate limit backend requires the API to be built with \"redis\" feature!")),
+      other => Err(format!("Unknown rate limit backend '{}'!", other))
+    }
+  }
 
   pub struct RateLimit;
   #[rocket::async_trait]
@@ -60,83 +663,729 @@ pub mod rate_limit {
     fn info(&self) -> fairing::Info {
       fairing::Info {
         name: "Rate Limiter",
-        kind: fairing::Kind::Ignite | fairing::Kind::Request
+        kind: fairing::Kind::Ignite | fairing::Kind::Response
       }
     }
 
     async fn on_ignite(&self, rocket: Rocket<Build>) -> Result {
-      let state: RateLimitState = Mutex::new(HashMap::new());
-
-      Ok(rocket.manage(state))
-    }
-
-    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
-      match req.guard::<&State<config::Config>>().await {
-        Outcome::Success(config) => {
-          match req.guard::<&State<RateLimitState>>().await {
-            Outcome::Success(state) => {
-              let time_window = config.max_requests_time_window.clone();
-              let max_requests = config.max_requests.clone();
-
-              match req.client_ip() {
-                Some(ip) => {
-                  match state.lock() {
-                    Ok(mut lock) => {
-                      match lock.contains_key(&ip) {
-                        true => {
-                          println!("Found \"{}\" in rate limit state.", ip.to_string());
-                          match lock.get_mut(&ip) {
-                            Some(entry) => {
-                              println!("\"{}\"'s entry - {} requests, last request: {}", ip.to_string(), entry.0, entry.1.to_string());
-
-                              if entry.0 < max_requests {
-                                println!("\"{}\" is not exceeding max requests limit. Reseting date...", ip.to_string());
-
-                                entry.1 = Utc::now().naive_utc();
-                              } else {
-                                println!("\"{}\" exceeded max requests limit.", ip.to_string());
-
-                                let current_time = Utc::now().naive_utc();
-                                let diff = current_time.signed_duration_since(entry.1).num_seconds();
-
-                                if diff >= time_window {
-                                  println!("Time window reached for \"{}\". Unlocking...", ip.to_string());
-                                  entry.0 = 1;
-                                } else {
-                                  println!("\"{}\" still within time window. Blocking...", ip.to_string());
-                                }
-                              }
-                            },
-                            None => {
-                              println!("Could not get entry from rate limit state!");
-                            }
-                          }
-                        },
-                        false => {
-                          println!("Adding \"{}\" to rate limit state.", ip.to_string());
-                          lock.insert(ip, (0, Utc::now().naive_utc()));
-                        }
-                      }
+      match rocket.state::<config::Config>() {
+        Some(config) => {
+          if let Some((route, name)) = config.rate_limit_routes.iter().find(|(_, name)| config.rate_limit_policy(name).is_none()) {
+            println!("Route '{}' is assigned to undefined rate limit policy '{}'!", route, name);
+            return Err(rocket);
+          }
+          if let Some((name, _)) = config.rate_limit_policies.iter().find(|(_, policy)| policy.max_requests < 1 || policy.time_window < 1) {
+            println!("Rate limit policy '{}' has to allow at least one request in a time window of at least one second!", name);
+            return Err(rocket);
+          }
+
+          let mut allowlist: Vec<Cidr> = Vec::new();
+
+          for entry in config.rate_limit_allowlist.iter() {
+            match Cidr::parse(entry) {
+              Some(cidr) => allowlist.push(cidr),
+              None => {
+                println!("'{}' in rate limit allowlist is not a valid IP address or CIDR range!", entry);
+                return Err(rocket);
+              }
+            }
+          }
+
+          match backend(config) {
+            Ok(state) => {
+              println!("Rate limiter initialized ({} backend)!", config.rate_limit_backend);
+              Ok(rocket.manage(state).manage(RateLimitAllowlist(allowlist)))
+            },
+            Err(message) => {
+              println!("{}", message);
+              Err(rocket)
+            }
+          }
+        },
+        None => Err(rocket)
+      }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+      let decision = match req.local_cache(RateLimitSlot::default).get() {
+        Some(decision) => Some(decision),
+        None => match (req.rocket().state::<config::Config>(), req.rocket().state::<RateLimitState>(), crate::guards::client_ip::client_ip(req)) {
+          (Some(config), Some(state), Some(ip)) => {
+            let (policy_name, policy) = crate::guards::rate_limit::route_policy(req, config);
+
+            match state.peek(&policy_name, &policy, &ip) {
+              Ok(decision) => Some(decision),
+              Err(message) => {
+                println!("{}", message);
+                None
+              }
+            }
+          },
+          _ => None
+        }
+      };
+
+      if let Some(decision) = decision {
+        res.set_header(Header::new("X-RateLimit-Limit", decision.limit.to_string()));
+        res.set_header(Header::new("X-RateLimit-Remaining", decision.remaining.to_string()));
+        res.set_header(Header::new("X-RateLimit-Reset", decision.reset_in.to_string()));
+
+        if !decision.allowed && res.status() == Status::TooManyRequests {
+          res.set_header(Header::new("Retry-After", decision.retry_after.to_string()));
+        }
+      }
+    }
+  }
+
+  pub struct RateLimitSweeper;
+
+  #[rocket::async_trait]
+  impl Fairing for RateLimitSweeper {
+    fn info(&self) -> fairing::Info {
+      fairing::Info {
+        name: "Rate Limit State Sweeper",
+        kind: fairing::Kind::Liftoff
+      }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+      match (rocket.state::<config::Config>(), rocket.state::<RateLimitState>()) {
+        (Some(config), Some(state)) => {
+          let interval = config.rate_limit_sweep_interval;
+          let state = state.clone();
+          let config = config.clone();
+
+          if interval == 0 {
+            println!("Rate limit state sweeper is disabled.");
+            return;
+          }
+
+          tokio::spawn(async move {
+            let mut timer = time::interval(std::time::Duration::from_secs(interval));
+
+            loop {
+              timer.tick().await;
+
+              match state.sweep(&config) {
+                Ok(evicted) if evicted > 0 => {
+                  println!("Rate limit state sweeper evicted {} stale client(s).", evicted);
+                },
+                Ok(_) => {},
+                Err(message) => {
+                  println!("Rate limit state sweeper failed: {}", message);
+                }
+              }
+            }
+          });
+        },
+        _ => {
+          println!("Could not start rate limit state sweeper!");
+        }
+      }
+    }
+  }
+
+  #[cfg(test)]
+  mod memory_tests {
+    use super::*;
+
+    fn client(last: u8) -> IpAddr {
+      IpAddr::from([127, 0, 0, last])
+    }
+
+    #[test]
+    fn hit_evicts_least_recently_hit_client() {
+      let backend = MemoryBackend::new(2);
+      let policy = RateLimitPolicy { max_requests: 1, time_window: 60 };
+
+      assert!(backend.hit("default", &policy, &client(1), 1).unwrap().allowed);
+      assert!(backend.hit("default", &policy, &client(2), 1).unwrap().allowed);
+      assert!(!backend.hit("default", &policy, &client(1), 1).unwrap().allowed);
+      assert!(backend.hit("default", &policy, &client(3), 1).unwrap().allowed);
+
+      let state = backend.state.lock().unwrap();
+
+      assert_eq!(state.counters.len(), 2);
+      assert_eq!(state.last_hits.len(), 2);
+      assert!(state.counters.contains_key(&(String::from("default"), client(1))));
+      assert!(!state.counters.contains_key(&(String::from("default"), client(2))));
+    }
+
+    #[test]
+    fn hit_limits_requests_in_window() {
+      let backend = MemoryBackend::new(10);
+      let policy = RateLimitPolicy { max_requests: 3, time_window: 3600 };
+
+      assert_eq!(backend.hit("default", &policy, &client(1), 2).unwrap().remaining, 1);
+      assert!(!backend.hit("default", &policy, &client(1), 2).unwrap().allowed);
+
+      let decision = backend.hit("default", &policy, &client(1), 1).unwrap();
+
+      assert!(decision.allowed);
+      assert_eq!(decision.remaining, 0);
+
+      let decision = backend.hit("default", &policy, &client(1), 1).unwrap();
+
+      assert!(!decision.allowed);
+      assert!(decision.retry_after > 0 && decision.retry_after <= 7200);
+      assert!(backend.hit("default", &policy, &client(2), 1).unwrap().allowed);
+    }
+
+    #[test]
+    fn peek_does_not_charge() {
+      let backend = MemoryBackend::new(10);
+      let policy = RateLimitPolicy { max_requests: 2, time_window: 3600 };
+
+      assert_eq!(backend.peek("default", &policy, &client(1)).unwrap().remaining, 2);
+      assert!(backend.state.lock().unwrap().counters.is_empty());
+
+      backend.hit("default", &policy, &client(1), 2).unwrap();
+
+      let decision = backend.peek("default", &policy, &client(1)).unwrap();
+
+      assert!(!decision.allowed);
+      assert_eq!(decision.remaining, 0);
+      assert_eq!(backend.peek("default", &policy, &client(1)).unwrap().remaining, 0);
+    }
+
+    #[test]
+    fn counter_weights_previous_window() {
+      let policy = RateLimitPolicy { max_requests: 10, time_window: 60 };
+      let mut counter = WindowCounter { window_start: 0, previous: 0, current: 10 };
+
+      counter.advance(75_000, 60_000);
+
+      assert_eq!(counter.window_start, 60_000);
+      assert_eq!((counter.previous, counter.current), (10, 0));
+      assert_eq!(counter.requests(75_000, 60_000), 8);
+      assert_eq!(counter.retry_after(75_000, 60_000, &policy, 3), 3_000);
+      assert_eq!(counter.reset_in(75_000, 60_000), 45_000);
+
+      counter.advance(190_000, 60_000);
+
+      assert_eq!((counter.previous, counter.current), (0, 0));
+      assert_eq!(counter.requests(190_000, 60_000), 0);
+    }
+
+    #[test]
+    fn sweep_removes_inactive_clients() {
+      let backend = MemoryBackend::new(10);
+      let config = config::Config::default();
+      let policy = config.rate_limit_policy("default").unwrap();
+
+      backend.hit("default", &policy, &client(1), 1).unwrap();
+      backend.hit("undefined", &policy, &client(2), 1).unwrap();
+      backend.hit("default", &policy, &client(3), 1).unwrap();
+      backend.state.lock().unwrap().counters.get_mut(&(String::from("default"), client(3))).unwrap().1.window_start = 0;
+
+      assert_eq!(backend.sweep(&config).unwrap(), 2);
+
+      let state = backend.state.lock().unwrap();
+
+      assert_eq!(state.counters.len(), 1);
+      assert_eq!(state.last_hits.len(), 1);
+    }
+  }
+
+  #[cfg(all(test, feature = "redis"))]
+  mod redis_tests {
+    use super::*;
+
+    fn backend() -> Option<(RedisBackend, String)> {
+      match std::env::var("REDIS_URL") {
+        Ok(redis_url) => Some((RedisBackend::new(&redis_url).unwrap(), format!("test-{}", nanoid::nanoid!(8)))),
+        Err(_) => {
+          println!("REDIS_URL is not set, skipping Redis rate limit backend test.");
+          None
+        }
+      }
+    }
+
+    fn cleanup(backend: &RedisBackend, policy_name: &str) {
+      let mut conn = backend.pool.get().unwrap();
+      let keys: Vec<String> = redis::cmd("KEYS")
+        .arg(format!("{}{}:*", RedisBackend::KEY_PREFIX, policy_name))
+        .query(&mut *conn)
+        .unwrap();
+
+      if !keys.is_empty() {
+        redis::cmd("DEL").arg(keys).query::<()>(&mut *conn).unwrap();
+      }
+    }
+
+    fn client() -> IpAddr {
+      IpAddr::from([127, 0, 0, 1])
+    }
+
+    #[test]
+    fn hit_limits_requests_in_window() {
+      let (backend, policy_name) = match backend() {
+        Some(backend) => backend,
+        None => return
+      };
+      let policy = RateLimitPolicy { max_requests: 3, time_window: 60 };
+
+      for remaining in (0..3).rev() {
+        let decision = backend.hit(&policy_name, &policy, &client(), 1).unwrap();
+
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, remaining);
+        assert_eq!(decision.limit, 3);
+      }
+
+      let decision = backend.hit(&policy_name, &policy, &client(), 1).unwrap();
+
+      assert!(!decision.allowed);
+      assert_eq!(decision.remaining, 0);
+      assert!(decision.retry_after > 0 && decision.retry_after <= 60);
+
+      let decision = backend.hit(&policy_name, &policy, &IpAddr::from([127, 0, 0, 2]), 1).unwrap();
+
+      assert!(decision.allowed);
+      cleanup(&backend, &policy_name);
+    }
+
+    #[test]
+    fn hit_charges_cost_atomically() {
+      let (backend, policy_name) = match backend() {
+        Some(backend) => backend,
+        None => return
+      };
+      let policy = RateLimitPolicy { max_requests: 5, time_window: 60 };
+
+      let decision = backend.hit(&policy_name, &policy, &client(), 4).unwrap();
+
+      assert!(decision.allowed);
+      assert_eq!(decision.remaining, 1);
+
+      let decision = backend.hit(&policy_name, &policy, &client(), 2).unwrap();
+
+      assert!(!decision.allowed);
+      assert_eq!(decision.remaining, 1);
+
+      let decision = backend.hit(&policy_name, &policy, &client(), 1).unwrap();
+
+      assert!(decision.allowed);
+      assert_eq!(decision.remaining, 0);
+      cleanup(&backend, &policy_name);
+    }
+
+    #[test]
+    fn hit_frees_requests_after_window() {
+      let (backend, policy_name) = match backend() {
+        Some(backend) => backend,
+        None => return
+      };
+      let policy = RateLimitPolicy { max_requests: 1, time_window: 1 };
+
+      assert!(backend.hit(&policy_name, &policy, &client(), 1).unwrap().allowed);
+      assert!(!backend.hit(&policy_name, &policy, &client(), 1).unwrap().allowed);
+
+      std::thread::sleep(std::time::Duration::from_millis(1100));
+
+      assert!(backend.hit(&policy_name, &policy, &client(), 1).unwrap().allowed);
+      cleanup(&backend, &policy_name);
+    }
+
+    #[test]
+    fn entries_lists_tracked_clients() {
+      let (backend, policy_name) = match backend() {
+        Some(backend) => backend,
+        None => return
+      };
+      let policy = RateLimitPolicy { max_requests: 10, time_window: 60 };
+      let config = config::Config {
+        rate_limit_policies: HashMap::from([(policy_name.clone(), policy.clone())]),
+        ..Default::default()
+      };
+
+      backend.hit(&policy_name, &policy, &client(), 2).unwrap();
+
+      let entries: Vec<ClientUsage> = backend.entries(&config).unwrap()
+        .into_iter()
+        .filter(|entry| entry.policy == policy_name)
+        .collect();
+
+      assert_eq!(entries.len(), 1);
+      assert_eq!(entries[0].client, "127.0.0.1");
+      assert_eq!(entries[0].requests, 2);
+      cleanup(&backend, &policy_name);
+    }
+  }
+}
+
+pub mod proxy {
+  use rocket::fairing::{self, Fairing, Result};
+  use rocket::{Rocket, Build};
+  use crate::config;
+  use crate::utils::Cidr;
+
+  pub struct TrustedProxies(pub Vec<Cidr>);
+
+  #[derive(Debug, Clone, Copy)]
+  pub enum ForwardedHeader {
+    Forwarded,
+    XForwardedFor
+  }
+
+  impl ForwardedHeader {
+    pub fn parse(name: &str) -> Option<Self> {
+      match name.to_lowercase().as_str() {
+        "forwarded" => Some(ForwardedHeader::Forwarded),
+        "x-forwarded-for" => Some(ForwardedHeader::XForwardedFor),
+        _ => None
+      }
+    }
+
+    pub fn name(&self) -> &'static str {
+      match self {
+        ForwardedHeader::Forwarded => "Forwarded",
+        ForwardedHeader::XForwardedFor => "X-Forwarded-For"
+      }
+    }
+  }
+
+  pub struct TrustedProxiesInitiator;
+
+  #[rocket::async_trait]
+  impl Fairing for TrustedProxiesInitiator {
+    fn info(&self) -> fairing::Info {
+      fairing::Info {
+        name: "Trusted Proxies Initiator",
+        kind: fairing::Kind::Ignite
+      }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> Result {
+      match rocket.state::<config::Config>() {
+        Some(config) => {
+          let mut proxies: Vec<Cidr> = Vec::new();
+
+          for entry in config.trusted_proxies.iter() {
+            match Cidr::parse(entry) {
+              Some(cidr) => proxies.push(cidr),
+              None => {
+                println!("'{}' in trusted proxies is not a valid IP address or CIDR range!", entry);
+                return Err(rocket);
+              }
+            }
+          }
+
+          let header = match ForwardedHeader::parse(&config.forwarded_header) {
+            Some(header) => header,
+            None => {
+              println!("'{}' is not a supported forwarding header! Use either 'X-Forwarded-For' or 'Forwarded'.", config.forwarded_header);
+              return Err(rocket);
+            }
+          };
+
+          Ok(rocket.manage(TrustedProxies(proxies)).manage(header))
+        },
+        None => Err(rocket)
+      }
+    }
+  }
+}
+
+pub mod ids {
+  use rocket::fairing::{self, Fairing, Result};
+  use rocket::{Rocket, Build};
+  use crate::config;
+  use crate::ids;
+  use crate::requests::{LinksSortBy, SortOrder};
+  use crate::store::{Store, ListQuery, LinksScope, CursorValue};
+
+  const SEED_PAGE_SIZE: i64 = 1000;
+
+  pub struct LinkIdGeneratorInitiator;
+
+  #[rocket::async_trait]
+  impl Fairing for LinkIdGeneratorInitiator {
+    fn info(&self) -> fairing::Info {
+      fairing::Info {
+        name: "Link ID Generator Initiator",
+        kind: fairing::Kind::Ignite
+      }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> Result {
+      match (rocket.state::<config::Config>(), rocket.state::<Store>()) {
+        (Some(config), Some(store)) => match ids::generator(config) {
+          Ok(generator) => {
+            let mut query = ListQuery {
+              scope: LinksScope::Public,
+              domain: None,
+              search: None,
+              created_after: None,
+              created_before: None,
+              sort_by: LinksSortBy::AddedAt,
+              order: SortOrder::Asc,
+              cursor: None,
+              offset: None,
+              limit: SEED_PAGE_SIZE
+            };
+
+            loop {
+              match store.list(&query) {
+                Ok((links, total)) => {
+                  if query.cursor.is_none() {
+                    generator.fit(total.max(0) as u64);
+                  }
+                  if !generator.seeds() {
+                    break;
+                  }
+
+                  for link in links.iter() {
+                    generator.seed(&link.link_id);
+                  }
+
+                  match links.last() {
+                    Some(last) if links.len() as i64 == SEED_PAGE_SIZE => {
+                      query.cursor = Some((CursorValue::AddedAt(last.added_at), last.link_id.clone()));
                     },
-                    Err(_) => {
-                      println!("Could not acquire lock on rate limit state!");
-                    }
+                    _ => break
                   }
                 },
-                None => {
-                  println!("Could not get client ip!");
+                Err(_) => {
+                  println!("Could not read links stored in database!");
+                  return Err(rocket);
                 }
               }
-            },
-            _ => {
-              println!("Could not retrieve rate limit state!");
             }
+
+            Ok(rocket.manage(generator))
+          },
+          Err(message) => {
+            println!("{}", message);
+            Err(rocket)
+          }
+        },
+        _ => Err(rocket)
+      }
+    }
+  }
+}
+
+pub mod reaper {
+  use std::time::Duration;
+  use chrono::{NaiveDateTime, Utc};
+  use diesel::prelude::*;
+  use diesel::dsl::not;
+  use diesel::sql_types::Bool;
+  use rocket::{Rocket, Orbit, fairing::{self, Fairing}};
+  use rocket::tokio::{self, time};
+  use std::sync::Arc;
+  use super::database::{Pool, DbBackend};
+  use crate::schema::links;
+  use crate::store::cache::LinkCache;
+  use crate::config;
+
+  const PURGE_BATCH_SIZE: i64 = 500;
+
+  pub struct LinkReaper;
+
+  #[rocket::async_trait]
+  impl Fairing for LinkReaper {
+    fn info(&self) -> fairing::Info {
+      fairing::Info {
+        name: "Expired Link Reaper",
+        kind: fairing::Kind::Liftoff
+      }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+      match (rocket.state::<config::Config>(), rocket.state::<Pool>(), rocket.state::<Arc<LinkCache>>()) {
+        (Some(config), Some(pool), Some(cache)) => {
+          let interval = config.reaper_interval;
+          let grace_period = config.reaper_grace_period;
+          let pool = pool.clone();
+          let cache = cache.clone();
+
+          if interval == 0 {
+            println!("Expired link reaper is disabled.");
+            return;
           }
+
+          tokio::spawn(async move {
+            let mut timer = time::interval(Duration::from_secs(interval));
+
+            loop {
+              timer.tick().await;
+
+              let pool = pool.clone();
+              let cache = cache.clone();
+
+              match tokio::task::spawn_blocking(move || reap(&pool, &cache, grace_period)).await {
+                Ok(Ok(purged)) => {
+                  println!("Expired link reaper purged {} link(s).", purged);
+                },
+                Ok(Err(message)) => {
+                  println!("Expired link reaper failed: {}", message);
+                },
+                Err(_) => {
+                  println!("Expired link reaper task has panicked!");
+                }
+              }
+            }
+          });
         },
         _ => {
-          println!("Could not acquire config!");
+          println!("Could not start expired link reaper!");
         }
       }
     }
   }
+
+  fn is_expired(now: NaiveDateTime) -> Box<dyn BoxableExpression<links::table, DbBackend, SqlType = Bool>> {
+    Box::new(
+      links::expires_at.is_not_null().and(links::expires_at.assume_not_null().le(now))
+        .or(links::max_visits.is_not_null().and(links::visit_count.ge(links::max_visits.assume_not_null())))
+    )
+  }
+
+  pub fn reap(pool: &Pool, cache: &LinkCache, grace_period: i64) -> Result<usize, String> {
+    let now = Utc::now().naive_utc();
+    let cutoff = now - chrono::Duration::seconds(grace_period);
+
+    let mut pool = match pool.get() {
+      Ok(pool) => pool,
+      Err(_) => return Err(String::from("Could not get database pool!"))
+    };
+    let conn = &mut *pool;
+
+    if diesel::update(links::table)
+      .filter(links::expired_at.is_not_null())
+      .filter(not(is_expired(now)))
+      .set(links::expired_at.eq(None::<NaiveDateTime>))
+      .execute(conn)
+      .is_err() {
+        return Err(String::from("Could not restore links which are no longer expired!"));
+      }
+
+    if diesel::update(links::table)
+      .filter(links::expired_at.is_null())
+      .filter(is_expired(now))
+      .set(links::expired_at.eq(Some(now)))
+      .execute(conn)
+      .is_err() {
+        return Err(String::from("Could not mark expired links!"));
+      }
+
+    let mut purged: usize = 0;
+
+    loop {
+      let link_keys = match links::table
+        .select(links::link_key)
+        .filter(links::expired_at.le(cutoff))
+        .filter(is_expired(now))
+        .limit(PURGE_BATCH_SIZE)
+        .load::<String>(conn) {
+          Ok(link_keys) => link_keys,
+          Err(_) => return Err(String::from("Could not fetch expired links from database!"))
+        };
+
+      if link_keys.is_empty() {
+        break;
+      }
+
+      match diesel::delete(links::table)
+        .filter(links::link_key.eq_any(&link_keys))
+        .filter(links::expired_at.le(cutoff))
+        .filter(is_expired(now))
+        .execute(conn) {
+          Ok(count) => {
+            purged += count;
+          },
+          Err(_) => return Err(String::from("Could not purge expired links from database!"))
+        }
+
+      for link_key in link_keys.iter() {
+        cache.invalidate(link_key);
+      }
+
+      if (link_keys.len() as i64) < PURGE_BATCH_SIZE {
+        break;
+      }
+    }
+
+    Ok(purged)
+  }
+
+  #[cfg(all(test, feature = "sqlite"))]
+  mod tests {
+    use super::*;
+    use crate::models;
+    use crate::store::{LinkStore, database::DatabaseLinkStore, cache::CachedLinkStore};
+    use super::super::database::test_pool;
+
+    fn new_link(link_id: &str, max_visits: Option<i32>) -> models::NewLink {
+      models::NewLink {
+        link_id: String::from(link_id),
+        target: String::from("https://example.com/"),
+        control_key: String::from("hash"),
+        expires_at: None,
+        max_visits: max_visits,
+        password: None,
+        owner_id: None,
+        target_host: Some(String::from("example.com")),
+        link_key: String::from(link_id)
+      }
+    }
+
+    fn age_expired_at(pool: &Pool, seconds: i64) {
+      diesel::update(links::table)
+        .filter(links::expired_at.is_not_null())
+        .set(links::expired_at.eq(Some(Utc::now().naive_utc() - chrono::Duration::seconds(seconds))))
+        .execute(&mut pool.get().unwrap())
+        .unwrap();
+    }
+
+    #[test]
+    fn reap_marks_then_purges_expired_links() {
+      let pool = test_pool();
+      let cache = Arc::new(LinkCache::new(10, 60));
+      let store = CachedLinkStore::new(Box::new(DatabaseLinkStore::new(pool.clone())), cache.clone());
+      let database = DatabaseLinkStore::new(pool.clone());
+      let expired = String::from("expired");
+      let active = String::from("active");
+
+      store.create(&new_link("expired", Some(1))).unwrap();
+      store.create(&new_link("active", None)).unwrap();
+      store.increment_visits(&expired).unwrap();
+      store.get(&expired).unwrap();
+
+      assert_eq!(reap(&pool, &cache, 3600).unwrap(), 0);
+      assert!(database.get(&expired).unwrap().unwrap().expired_at.is_some());
+      assert!(database.get(&active).unwrap().unwrap().expired_at.is_none());
+
+      age_expired_at(&pool, 7200);
+
+      assert_eq!(reap(&pool, &cache, 3600).unwrap(), 1);
+      assert!(cache.get(&expired).is_none());
+      assert!(store.get(&expired).unwrap().is_none());
+      assert!(store.get(&active).unwrap().is_some());
+    }
+
+    #[test]
+    fn reap_keeps_links_extended_after_being_marked() {
+      let pool = test_pool();
+      let cache = Arc::new(LinkCache::new(10, 60));
+      let store = CachedLinkStore::new(Box::new(DatabaseLinkStore::new(pool.clone())), cache.clone());
+      let link_id = String::from("extended");
+
+      store.create(&new_link("extended", Some(1))).unwrap();
+      store.increment_visits(&link_id).unwrap();
+
+      assert_eq!(reap(&pool, &cache, 3600).unwrap(), 0);
+
+      age_expired_at(&pool, 7200);
+      store.update(&link_id, &models::LinkChanges {
+        max_visits: Some(Some(10)),
+        ..Default::default()
+      }).unwrap();
+
+      assert_eq!(reap(&pool, &cache, 3600).unwrap(), 0);
+      assert!(store.get(&link_id).unwrap().unwrap().expired_at.is_none());
+    }
+  }
 }
\ No newline at end of file
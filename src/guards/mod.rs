@@ -1,7 +1,8 @@
 pub mod rate_limit {
-  use chrono::Utc;
+  use std::net::IpAddr;
   use rocket::{request::{FromRequest, Outcome, Request}, http::Status, State};
   use crate::{fairings::rate_limit, config};
+  use crate::responses::{ResponseData, ResponseErrorType, errors};
 
   #[derive(Debug)]
   pub enum RateLimit {
@@ -10,6 +11,21 @@ pub mod rate_limit {
     Error
   }
 
+  pub fn route_policy(req: &Request<'_>, config: &config::Config) -> (String, config::RateLimitPolicy) {
+    let route = req.route()
+      .map(|route| route.uri.path().to_string())
+      .unwrap_or_default();
+
+    config.route_rate_limit_policy(req.method().as_str(), &route)
+  }
+
+  async fn is_allowlisted(req: &Request<'_>, ip: &IpAddr) -> bool {
+    match req.guard::<&State<rate_limit::RateLimitAllowlist>>().await {
+      Outcome::Success(allowlist) => allowlist.contains(ip),
+      _ => false
+    }
+  }
+
   #[rocket::async_trait]
   impl<'r> FromRequest<'r> for RateLimit {
     type Error = RateLimit;
@@ -18,50 +34,469 @@ pub mod rate_limit {
       match req.guard::<&State<config::Config>>().await {
         Outcome::Success(config) => match req.guard::<&State<rate_limit::RateLimitState>>().await {
           Outcome::Success(state) => {
-            let max_requests = config.max_requests;
-            let time_window = config.max_requests_time_window;
-            let current_time = Utc::now().naive_utc();
-
-            match req.client_ip() {
-              Some(ip) => match state.lock() {
-                Ok(mut lock) => match lock.get_mut(&ip) {
-                  Some(entry) => {
-                    let diff = current_time.signed_duration_since(entry.1).num_seconds();
-
-                    if entry.0 >= max_requests {
-                      Outcome::Failure((Status::TooManyRequests, RateLimit::Rejected(time_window - diff)))
-                    } else {
-                      entry.0 = entry.0 + 1;
-                      Outcome::Success(RateLimit::Allowed)
-                    }
-                  },
-                  None => {
-                    println!("Could not find client IP's entry in rate limit state!");
-                    Outcome::Failure((Status::InternalServerError, RateLimit::Error))
+            let (policy_name, policy) = route_policy(req, config);
+            let allowlisted = match super::client_ip::client_ip(req) {
+              Some(ip) => is_allowlisted(req, &ip).await,
+              None => false
+            };
+
+            match super::client_ip::client_ip(req) {
+              Some(_) if allowlisted => Outcome::Success(RateLimit::Allowed),
+              Some(ip) => match state.hit(&policy_name, &policy, &ip, 1) {
+                Ok(decision) => {
+                  req.local_cache(rate_limit::RateLimitSlot::default).set(decision);
+
+                  if decision.allowed {
+                    Outcome::Success(RateLimit::Allowed)
+                  } else {
+                    Outcome::Error((Status::TooManyRequests, RateLimit::Rejected(decision.retry_after)))
                   }
                 },
-                Err(_) => {
-                  println!("Could not acquire lock on rate limit state!");
-                  Outcome::Failure((Status::InternalServerError, RateLimit::Error))
+                Err(message) => {
+                  println!("{}", message);
+                  Outcome::Error((Status::InternalServerError, RateLimit::Error))
                 }
               },
               None => {
                 println!("Could not get client's IP!");
-                Outcome::Failure((Status::InternalServerError, RateLimit::Error))
+                Outcome::Error((Status::InternalServerError, RateLimit::Error))
               }
             }
           },
           _ => {
             println!("Could not acquire rate limit state!");
-            Outcome::Failure((Status::InternalServerError, RateLimit::Error))
+            Outcome::Error((Status::InternalServerError, RateLimit::Error))
           }
         },
         _ => {
           println!("Could not acquire config!");
-          Outcome::Failure((Status::InternalServerError, RateLimit::Error))
+          Outcome::Error((Status::InternalServerError, RateLimit::Error))
         }
       }
       
     }
   }
+
+  pub struct BulkRateLimit<'r> {
+    state: &'r rate_limit::RateLimitState,
+    slot: &'r rate_limit::RateLimitSlot,
+    client: IpAddr,
+    allowlisted: bool,
+    policy_name: String,
+    policy: config::RateLimitPolicy
+  }
+
+  impl<'r> BulkRateLimit<'r> {
+    pub fn charge(&self, items: usize) -> Result<(), ResponseData<()>> {
+      let res_data = ResponseData::new();
+      let items = items.max(1) as i64;
+
+      if self.allowlisted {
+        return Ok(());
+      }
+      if items > self.policy.max_requests {
+        return Err(
+          res_data.error(
+            Status::PayloadTooLarge,
+            ResponseErrorType::BulkRequestExceedingSizeError,
+            format!("Bulk request contains {} items, but at most {} can be sent at once!", items, self.policy.max_requests),
+            None
+          )
+        );
+      }
+
+      match self.state.hit(&self.policy_name, &self.policy, &self.client, items) {
+        Ok(decision) => {
+          self.slot.set(decision);
+
+          if decision.allowed {
+            Ok(())
+          } else {
+            Err(
+              res_data.error(
+                Status::TooManyRequests,
+                ResponseErrorType::RateLimitedError,
+                String::from("You have been rate limited!"),
+                Some(errors::Errors::RateLimitedError {
+                  max_requests: decision.limit,
+                  time_window: decision.time_window,
+                  cooldown: decision.retry_after
+                })
+              )
+            )
+          }
+        },
+        Err(message) => Err(
+          res_data.error(
+            Status::InternalServerError,
+            ResponseErrorType::UndefinedError,
+            message,
+            None
+          )
+        )
+      }
+    }
+  }
+
+  #[rocket::async_trait]
+  impl<'r> FromRequest<'r> for BulkRateLimit<'r> {
+    type Error = RateLimit;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+      match (req.guard::<&State<config::Config>>().await, req.guard::<&State<rate_limit::RateLimitState>>().await) {
+        (Outcome::Success(config), Outcome::Success(state)) => match super::client_ip::client_ip(req) {
+          Some(ip) => {
+            let (policy_name, policy) = route_policy(req, config);
+
+            Outcome::Success(BulkRateLimit {
+              state: state.inner(),
+              slot: req.local_cache(rate_limit::RateLimitSlot::default),
+              client: ip,
+              allowlisted: is_allowlisted(req, &ip).await,
+              policy_name: policy_name,
+              policy: policy
+            })
+          },
+          None => {
+            println!("Could not get client's IP!");
+            Outcome::Error((Status::InternalServerError, RateLimit::Error))
+          }
+        },
+        _ => {
+          println!("Could not acquire rate limit state!");
+          Outcome::Error((Status::InternalServerError, RateLimit::Error))
+        }
+      }
+    }
+  }
+}
+
+pub mod client_ip {
+  use std::net::{IpAddr, SocketAddr};
+  use rocket::request::{FromRequest, Outcome, Request};
+  use rocket::http::Status;
+  use crate::fairings::proxy::{TrustedProxies, ForwardedHeader};
+
+  #[derive(Debug, Clone, Copy)]
+  pub struct ClientIp(pub IpAddr);
+
+  struct ResolvedIp(Option<IpAddr>);
+
+  fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+
+    if let Some(node) = node.strip_prefix('[') {
+      return node.split(']').next()?.parse::<IpAddr>().ok();
+    }
+
+    match node.parse::<IpAddr>() {
+      Ok(ip) => Some(ip),
+      Err(_) => node.parse::<SocketAddr>().ok().map(|addr| addr.ip())
+    }
+  }
+
+  fn forwarded_chain(req: &Request<'_>, header: ForwardedHeader) -> Option<Vec<Option<IpAddr>>> {
+    let headers = req.headers();
+
+    if !headers.contains(header.name()) {
+      return None;
+    }
+
+    match header {
+      ForwardedHeader::Forwarded => Some(
+        headers.get(header.name())
+          .flat_map(|value| value.split(','))
+          .map(|element| {
+            element.split(';')
+              .filter_map(|pair| pair.split_once('='))
+              .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+              .and_then(|(_, node)| parse_node(node))
+          })
+          .collect()
+      ),
+      ForwardedHeader::XForwardedFor => Some(
+        headers.get(header.name())
+          .flat_map(|value| value.split(','))
+          .map(parse_node)
+          .collect()
+      )
+    }
+  }
+
+  pub fn resolve(req: &Request<'_>) -> Option<IpAddr> {
+    let peer = req.remote()?.ip();
+    let trusted = match req.rocket().state::<TrustedProxies>() {
+      Some(trusted) => trusted,
+      None => return Some(peer)
+    };
+    let is_trusted = |ip: &IpAddr| trusted.0.iter().any(|cidr| cidr.contains(ip));
+
+    if !is_trusted(&peer) {
+      return Some(peer);
+    }
+
+    let mut client = peer;
+    let header = req.rocket().state::<ForwardedHeader>()
+      .copied()
+      .unwrap_or(ForwardedHeader::XForwardedFor);
+
+    if let Some(chain) = forwarded_chain(req, header) {
+      for node in chain.iter().rev() {
+        match node {
+          Some(ip) if is_trusted(ip) => {
+            client = *ip;
+          },
+          Some(ip) => return Some(*ip),
+          None => break
+        }
+      }
+    }
+
+    Some(client)
+  }
+
+  pub fn client_ip(req: &Request<'_>) -> Option<IpAddr> {
+    req.local_cache(|| ResolvedIp(resolve(req))).0
+  }
+
+  #[rocket::async_trait]
+  impl<'r> FromRequest<'r> for ClientIp {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+      match client_ip(req) {
+        Some(ip) => Outcome::Success(ClientIp(ip)),
+        None => Outcome::Error((Status::BadRequest, ()))
+      }
+    }
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+    use rocket::http::Header;
+    use rocket::local::blocking::Client;
+    use crate::utils::Cidr;
+
+    fn client(header: Option<ForwardedHeader>) -> Client {
+      let rocket = rocket::build().manage(TrustedProxies(vec![Cidr::parse("10.0.0.0/8").unwrap()]));
+
+      match header {
+        Some(header) => Client::untracked(rocket.manage(header)).unwrap(),
+        None => Client::untracked(rocket).unwrap()
+      }
+    }
+
+    fn resolve_from(client: &Client, peer: &str, header: Option<(&'static str, &'static str)>) -> Option<IpAddr> {
+      let mut req = client.get("/").remote(format!("{}:4000", peer).parse().unwrap());
+
+      if let Some((name, value)) = header {
+        req = req.header(Header::new(name, value));
+      }
+
+      resolve(req.inner())
+    }
+
+    fn ip(ip: &str) -> Option<IpAddr> {
+      Some(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn resolve_uses_peer_without_header() {
+      let client = client(None);
+
+      assert_eq!(resolve_from(&client, "10.0.0.1", None), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn resolve_ignores_header_from_untrusted_peer() {
+      let client = client(None);
+
+      assert_eq!(resolve_from(&client, "1.1.1.1", Some(("X-Forwarded-For", "2.2.2.2"))), ip("1.1.1.1"));
+    }
+
+    #[test]
+    fn resolve_stops_at_rightmost_untrusted_hop() {
+      let client = client(None);
+
+      assert_eq!(resolve_from(&client, "10.0.0.1", Some(("X-Forwarded-For", "1.1.1.1, 10.0.0.2"))), ip("1.1.1.1"));
+      assert_eq!(resolve_from(&client, "10.0.0.1", Some(("X-Forwarded-For", "1.1.1.1, 2.2.2.2, 10.0.0.2"))), ip("2.2.2.2"));
+      assert_eq!(resolve_from(&client, "10.0.0.1", Some(("X-Forwarded-For", "10.0.0.3, 10.0.0.2"))), ip("10.0.0.3"));
+    }
+
+    #[test]
+    fn resolve_stops_at_unparseable_hop() {
+      let client = client(None);
+
+      assert_eq!(resolve_from(&client, "10.0.0.1", Some(("X-Forwarded-For", "1.1.1.1, unknown, 10.0.0.2"))), ip("10.0.0.2"));
+      assert_eq!(resolve_from(&client, "10.0.0.1", Some(("X-Forwarded-For", "1.1.1.1, "))), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn resolve_reads_configured_header() {
+      let client = client(Some(ForwardedHeader::Forwarded));
+
+      assert_eq!(resolve_from(&client, "10.0.0.1", Some(("Forwarded", "for=\"[2001:db8::1]:4711\";proto=https, for=10.0.0.2"))), ip("2001:db8::1"));
+      assert_eq!(resolve_from(&client, "10.0.0.1", Some(("Forwarded", "for=_hidden, for=10.0.0.2"))), ip("10.0.0.2"));
+      assert_eq!(resolve_from(&client, "10.0.0.1", Some(("X-Forwarded-For", "1.1.1.1"))), ip("10.0.0.1"));
+    }
+  }
+}
+
+pub mod visitor {
+  use std::convert::Infallible;
+  use rocket::request::{FromRequest, Outcome, Request};
+  use crate::utils;
+
+  #[derive(Debug, Clone)]
+  pub struct Visitor {
+    pub referrer: Option<String>,
+    pub user_agent: Option<String>,
+    pub client_ip: Option<String>
+  }
+
+  #[rocket::async_trait]
+  impl<'r> FromRequest<'r> for Visitor {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+      let headers = req.headers();
+
+      Outcome::Success(Visitor {
+        referrer: headers.get_one("Referer").map(String::from),
+        user_agent: headers.get_one("User-Agent").map(String::from),
+        client_ip: super::client_ip::client_ip(req).map(utils::anonymize_ip)
+      })
+    }
+  }
+}
+
+pub mod control_key {
+  use std::convert::Infallible;
+  use rocket::request::{FromRequest, Outcome, Request};
+
+  #[derive(Debug, Clone)]
+  pub struct ControlKey(pub Option<String>);
+
+  #[rocket::async_trait]
+  impl<'r> FromRequest<'r> for ControlKey {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+      Outcome::Success(ControlKey(
+        req.headers().get_one("X-Control-Key").map(String::from)
+      ))
+    }
+  }
+}
+
+pub mod auth {
+  use rocket::{request::{FromRequest, Outcome, Request}, http::Status, State};
+  use diesel::prelude::*;
+  use crate::fairings::database::Pool;
+  use crate::models;
+  use crate::utils;
+
+  #[derive(Debug)]
+  pub enum Auth {
+    User(i64),
+    Anonymous,
+    Invalid,
+    Error
+  }
+
+  impl Auth {
+    pub fn user_id(&self) -> Option<i64> {
+      match self {
+        Auth::User(user_id) => Some(*user_id),
+        _ => None
+      }
+    }
+  }
+
+  #[rocket::async_trait]
+  impl<'r> FromRequest<'r> for Auth {
+    type Error = Auth;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, <crate::guards::auth::Auth as FromRequest<'r>>::Error> {
+      match req.headers().get_one("Authorization") {
+        Some(header) => match header.strip_prefix("Bearer ").and_then(|token| token.split_once('.')) {
+          Some((key_id, secret)) => match req.guard::<&State<Pool>>().await {
+            Outcome::Success(db) => match db.get() {
+              Ok(mut pool) => {
+                use crate::schema::api_keys;
+                let conn = &mut *pool;
+
+                match api_keys::table
+                  .find(key_id)
+                  .limit(1)
+                  .load::<models::ApiKey>(conn) {
+                    Ok(keys) if !keys.is_empty() && keys[0].key_hash == utils::hash_api_key(secret) => Outcome::Success(Auth::User(keys[0].user_id)),
+                    Ok(_) => Outcome::Error((Status::Unauthorized, Auth::Invalid)),
+                    Err(_) => {
+                      println!("Could not fetch API key from database!");
+                      Outcome::Error((Status::InternalServerError, Auth::Error))
+                    }
+                  }
+              },
+              Err(_) => {
+                println!("Could not get database pool!");
+                Outcome::Error((Status::InternalServerError, Auth::Error))
+              }
+            },
+            _ => {
+              println!("Could not acquire database pool state!");
+              Outcome::Error((Status::InternalServerError, Auth::Error))
+            }
+          },
+          None => Outcome::Error((Status::Unauthorized, Auth::Invalid))
+        },
+        None => Outcome::Success(Auth::Anonymous)
+      }
+    }
+  }
+}
+
+pub mod admin {
+  use rocket::{request::{FromRequest, Outcome, Request}, http::Status, State};
+  use crate::config;
+
+  #[derive(Debug)]
+  pub enum Admin {
+    Authorized,
+    Unauthorized,
+    Disabled,
+    Error
+  }
+
+  fn tokens_equal(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+      return false;
+    }
+
+    a.bytes()
+      .zip(b.bytes())
+      .fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+  }
+
+  #[rocket::async_trait]
+  impl<'r> FromRequest<'r> for Admin {
+    type Error = Admin;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, <crate::guards::admin::Admin as FromRequest<'r>>::Error> {
+      match req.guard::<&State<config::Config>>().await {
+        Outcome::Success(config) => match &config.admin_token {
+          Some(admin_token) => match req.headers().get_one("Authorization").and_then(|header| header.strip_prefix("Bearer ")) {
+            Some(token) if tokens_equal(token, admin_token) => Outcome::Success(Admin::Authorized),
+            _ => Outcome::Error((Status::Unauthorized, Admin::Unauthorized))
+          },
+          None => Outcome::Error((Status::Unauthorized, Admin::Disabled))
+        },
+        _ => {
+          println!("Could not acquire config!");
+          Outcome::Error((Status::InternalServerError, Admin::Error))
+        }
+      }
+    }
+  }
 }
\ No newline at end of file
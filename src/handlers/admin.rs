@@ -0,0 +1,87 @@
+use rocket::{http::Status, State};
+use crate::responses::*;
+use crate::models;
+use crate::config::Config;
+use crate::store::{LinkStore, cache::LinkCache};
+use crate::fairings::rate_limit::RateLimitState;
+
+pub fn set_disabled(link_id: &String, disabled: bool, store: &dyn LinkStore) -> Result<(), ResponseData<()>> {
+  let res_data = ResponseData::new();
+  let changes = models::LinkChanges {
+    disabled: Some(disabled),
+    ..Default::default()
+  };
+
+  match store.update(link_id, &changes) {
+    Ok(()) => Ok(()),
+    Err(e) => Err(
+      errors::Errors::store_error(res_data, &e, link_id, format!("Could not update link with ID '{}' due to database error!", link_id))
+    )
+  }
+}
+
+pub fn reset_visits(link_id: &String, store: &dyn LinkStore) -> Result<(), ResponseData<()>> {
+  let res_data = ResponseData::new();
+  let changes = models::LinkChanges {
+    visit_count: Some(0),
+    ..Default::default()
+  };
+
+  match store.update(link_id, &changes) {
+    Ok(()) => Ok(()),
+    Err(e) => Err(
+      errors::Errors::store_error(res_data, &e, link_id, format!("Could not reset visit count of link with ID '{}' due to database error!", link_id))
+    )
+  }
+}
+
+pub fn delete_link(link_id: &String, store: &dyn LinkStore) -> Result<(), ResponseData<()>> {
+  let res_data = ResponseData::new();
+
+  match store.delete(link_id) {
+    Ok(()) => Ok(()),
+    Err(e) => Err(
+      errors::Errors::store_error(res_data, &e, link_id, format!("Could not delete link with ID '{}' from database!", link_id))
+    )
+  }
+}
+
+pub fn rate_limit_entries(state: &State<RateLimitState>, config: &Config) -> Result<Vec<successes::RateLimitEntry>, ResponseData<()>> {
+  let res_data = ResponseData::new();
+
+  match state.entries(config) {
+    Ok(entries) => {
+      let mut entries = entries.into_iter()
+        .map(|entry| {
+          successes::RateLimitEntry {
+            ip: entry.client,
+            policy: entry.policy,
+            requests: entry.requests,
+            reset_at: entry.reset_at
+          }
+        })
+        .collect::<Vec<successes::RateLimitEntry>>();
+      entries.sort_by(|a, b| b.requests.cmp(&a.requests).then_with(|| a.ip.cmp(&b.ip)).then_with(|| a.policy.cmp(&b.policy)));
+
+      Ok(entries)
+    },
+    Err(message) => Err(
+      res_data.error(
+        Status::InternalServerError,
+        ResponseErrorType::UndefinedError,
+        message,
+        None
+      )
+    )
+  }
+}
+
+pub fn cache_stats(cache: &LinkCache) -> successes::CacheStats {
+  successes::CacheStats {
+    size: cache.size(),
+    capacity: cache.capacity(),
+    ttl: cache.ttl(),
+    hits: cache.hits(),
+    misses: cache.misses()
+  }
+}
\ No newline at end of file
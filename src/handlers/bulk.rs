@@ -1,13 +1,16 @@
-use rocket::{http::Status, State};
-use diesel::prelude::*;
+use std::collections::HashSet;
+use serde::Serialize;
+use rocket::http::Status;
 use bcrypt;
 use crate::requests;
 use crate::responses::*;
 use crate::config::Config;
-use crate::fairings::database::Pool;
+use crate::ids::LinkIdGenerator;
+use crate::store::{LinkStore, StoreError};
 use crate::models;
+use crate::utils;
 
-pub fn add_links(links: Vec<requests::NewLink>, db: &State<Pool>, config: &State<Config>) -> Response<Vec<successes::NewLinkResult>> {
+pub fn add_links(links: Vec<requests::NewLink>, user_id: Option<i64>, ids: &LinkIdGenerator, store: &dyn LinkStore, config: &Config) -> Response<Vec<successes::NewLinkResult>> {
   let mut res_data = ResponseData::<Vec<successes::NewLinkResult>>::new();
   let mut new_links: Vec<successes::NewLinkResult> = Vec::new();
   let mut success = true;
@@ -15,7 +18,7 @@ pub fn add_links(links: Vec<requests::NewLink>, db: &State<Pool>, config: &State
 
   for link in links.iter() {
     i += 1;
-    match super::utils::add_link(link, db, config) {
+    match super::utils::add_link(link, ids, config) {
       Ok(r) => {
         new_links.push(r);
       },
@@ -33,77 +36,615 @@ pub fn add_links(links: Vec<requests::NewLink>, db: &State<Pool>, config: &State
   }
 
   if success {
-    match db.get() {
-      Ok(mut pool) => {
-        use crate::schema::links;
-        let conn = &mut *pool;
-        let mut success = true;
-        let mut i: u32 = 0;
-        let mut new_links_db: Vec<models::NewLink> = Vec::new();
-
-        for new_link in new_links.iter() {
-          i += 1;
-          let link_id = &new_link.link_id;
-          let control_key = &new_link.control_key;
-          let target = &new_link.target;
-
-          match bcrypt::hash(control_key, bcrypt::DEFAULT_COST) {
-            Ok(hash) => {
-              let new_link_db = models::NewLink {
-                link_id: link_id.clone(),
-                control_key: hash,
-                target: target.clone()
-              };
-
-              new_links_db.push(new_link_db);
-            },
-            Err(_) => {
-              let req_data: ResponseData<Vec<successes::NewLinkResult>> = ResponseData::new().error(
-                Status::InternalServerError,
-                ResponseErrorType::ControlKeyHashGenerationError,
-                format!("Could not generate bcrypt hash of control key for link with ID '{}'.", link_id),
-                None
-              );
-
-              res_data = errors::Errors::bulk_request_error(
-                res_data, 
-                req_data.clone_status(), 
-                i, 
-                req_data
-              );
-              success = false;
-              break;
-            }
+    let mut success = true;
+    let mut i: u32 = 0;
+    let mut new_links_db: Vec<models::NewLink> = Vec::new();
+
+    for (new_link, link) in new_links.iter().zip(links.iter()) {
+      i += 1;
+      let link_id = &new_link.link_id;
+      let control_key = &new_link.control_key;
+      let target = &new_link.target;
+
+      match bcrypt::hash(control_key, bcrypt::DEFAULT_COST) {
+        Ok(hash) => match super::utils::hash_password::<()>(&link.password) {
+          Ok(password) => {
+            let new_link_db = models::NewLink {
+              link_id: link_id.clone(),
+              control_key: hash,
+              target: target.clone(),
+              expires_at: new_link.expires_at,
+              max_visits: new_link.max_visits,
+              password: password,
+              owner_id: user_id,
+              target_host: utils::target_host(target),
+              link_key: utils::link_key(link_id, config.case_insensitive_ids)
+            };
+
+            new_links_db.push(new_link_db);
+          },
+          Err(e) => {
+            res_data = errors::Errors::bulk_request_error(
+              res_data,
+              e.clone_status(),
+              i,
+              e
+            );
+            success = false;
+            break;
           }
+        },
+        Err(_) => {
+          let req_data: ResponseData<Vec<successes::NewLinkResult>> = ResponseData::new().error(
+            Status::InternalServerError,
+            ResponseErrorType::ControlKeyHashGenerationError,
+            format!("Could not generate bcrypt hash of control key for link with ID '{}'.", link_id),
+            None
+          );
+
+          res_data = errors::Errors::bulk_request_error(
+            res_data, 
+            req_data.clone_status(), 
+            i, 
+            req_data
+          );
+          success = false;
+          break;
         }
+      }
+    }
+
+    if success {
+      let auto_ids: Vec<bool> = links.iter()
+        .map(|link| link.link_id.is_none())
+        .collect();
+
+      match super::utils::create_links(&mut new_links, &mut new_links_db, &auto_ids, true, ids, store, config) {
+        Ok(results) => match results.iter().position(|result| result.is_err()) {
+          Some(i) => {
+            let link_id = &new_links_db[i].link_id;
+            let req_data: ResponseData<()> = match &results[i] {
+              Err(e) => errors::Errors::store_error(
+                ResponseData::new(),
+                e,
+                link_id,
+                format!("Could not add link with ID '{}' to database!", link_id)
+              ),
+              Ok(()) => ResponseData::new()
+            };
 
-        if success {
-          match diesel::insert_into(links::table)
-            .values(&new_links_db)
-            .execute(conn) {
-              Ok(_) => {
-                res_data = res_data.success(
-                  Status::Ok,
-                  Some(new_links)
-                );
-              },
-              Err(_) => {
-                res_data = res_data.error(
-                  Status::InternalServerError,
-                  ResponseErrorType::DatabaseError,
-                  String::from("Could not add links to database!"),
-                  None
-                );
-              }
-            }
+            res_data = errors::Errors::bulk_request_error(
+              res_data,
+              req_data.clone_status(),
+              i as u32 + 1,
+              req_data
+            );
+          },
+          None => {
+            res_data = res_data.success(
+              Status::Ok,
+              Some(new_links)
+            );
+          }
+        },
+        Err(_) => {
+          res_data = res_data.error(
+            Status::InternalServerError,
+            ResponseErrorType::DatabaseError,
+            String::from("Could not add links to database!"),
+            None
+          );
         }
+      }
+    }
+  }
+
+  res_data
+    .to_response()
+}
+
+fn duplicate_in_request<S: Serialize>(link_id: &String) -> ResponseData<S> {
+  ResponseData::new().error(
+    Status::Conflict,
+    ResponseErrorType::DuplicateIdError,
+    format!("Link with ID '{}' appears more than once in bulk request!", link_id),
+    None
+  )
+}
+
+fn apply_results<S, F>(res_data: ResponseData<S>, results: Result<Vec<Result<(), StoreError>>, StoreError>, link_ids: &Vec<String>, error_message: F) -> Result<ResponseData<S>, Response<S>>
+where
+  S: Serialize,
+  F: Fn(usize) -> String
+{
+  match results {
+    Ok(results) => match results.into_iter().enumerate().find(|(_, result)| result.is_err()) {
+      Some((i, Err(e))) => {
+        let req_data: ResponseData<()> = errors::Errors::store_error(ResponseData::new(), &e, &link_ids[i], error_message(i));
+
+        Err(
+          errors::Errors::bulk_request_error(res_data, req_data.clone_status(), i as u32 + 1, req_data)
+            .to_response()
+        )
       },
+      _ => Ok(res_data)
+    },
+    Err(_) => Err(
+      res_data
+        .error(
+          Status::InternalServerError,
+          ResponseErrorType::DatabaseError,
+          String::from("Could not apply bulk request to database!"),
+          None
+        )
+        .to_response()
+    )
+  }
+}
+
+pub fn delete_links(links: Vec<requests::DeleteLink>, user_id: Option<i64>, store: &dyn LinkStore, config: &Config) -> Response<()> {
+  let res_data = ResponseData::<()>::new();
+  let mut link_ids: HashSet<String> = HashSet::new();
+
+  for (i, link) in links.iter().enumerate() {
+    let result = match link_ids.insert(utils::link_key(&link.link_id, config.case_insensitive_ids)) {
+      true => super::utils::delete_link(&link.link_id, &link.control_key, user_id, store),
+      false => Err(duplicate_in_request(&link.link_id))
+    };
+
+    if let Err(e) = result {
+      return errors::Errors::bulk_request_error(res_data, e.clone_status(), i as u32 + 1, e)
+        .to_response();
+    }
+  }
+
+  let link_ids: Vec<String> = links.iter()
+    .map(|link| link.link_id.clone())
+    .collect();
+  let results = store.delete_batch(&link_ids);
+
+  match apply_results(res_data, results, &link_ids, |i| format!("Could not delete link with ID '{}' from database!", link_ids[i])) {
+    Ok(res_data) => res_data
+      .success(Status::Ok, None)
+      .to_response(),
+    Err(response) => response
+  }
+}
+
+pub fn edit_links(links: Vec<requests::EditLink>, user_id: Option<i64>, store: &dyn LinkStore, config: &Config) -> Response<Vec<successes::EditLinkResult>> {
+  let res_data = ResponseData::<Vec<successes::EditLinkResult>>::new();
+  let mut link_ids: HashSet<String> = HashSet::new();
+  let mut new_link_ids: HashSet<String> = HashSet::new();
+  let mut edited_links: Vec<successes::EditLinkResult> = Vec::new();
+
+  for (i, link) in links.iter().enumerate() {
+    let result = match link_ids.insert(utils::link_key(&link.link_id, config.case_insensitive_ids)) {
+      true => super::utils::edit_link(link, user_id, store, config),
+      false => Err(duplicate_in_request(&link.link_id))
+    };
+    let result = match result {
+      Ok(edited) if link.new_link_id.is_some() && !new_link_ids.insert(utils::link_key(&edited.link_id, config.case_insensitive_ids)) => Err(duplicate_in_request(&edited.link_id)),
+      result => result
+    };
+
+    match result {
+      Ok(edited) => edited_links.push(edited),
+      Err(e) => {
+        return errors::Errors::bulk_request_error(res_data, e.clone_status(), i as u32 + 1, e)
+          .to_response();
+      }
+    }
+  }
+
+  let changes: Vec<(String, models::LinkChanges)> = edited_links.iter()
+    .zip(links.iter())
+    .map(|(edited, link)| (link.link_id.clone(), super::utils::link_changes(edited, config)))
+    .collect();
+  let edited_link_ids: Vec<String> = edited_links.iter()
+    .map(|edited| edited.link_id.clone())
+    .collect();
+  let results = store.update_batch(&changes);
+
+  match apply_results(res_data, results, &edited_link_ids, |i| format!("Could not update link with ID '{}' due to database error!", links[i].link_id)) {
+    Ok(res_data) => res_data
+      .success(Status::Ok, Some(edited_links))
+      .to_response(),
+    Err(response) => response
+  }
+}
+
+fn prepare_link(link: &requests::NewLink, link_ids: &HashSet<String>, custom_ids: &HashSet<String>, user_id: Option<i64>, ids: &LinkIdGenerator, config: &Config) -> Result<(successes::NewLinkResult, models::NewLink), ResponseData<()>> {
+  let mut new_link = super::utils::add_link(link, ids, config)?;
+
+  if link.link_id.is_some() && link_ids.contains(&utils::link_key(&new_link.link_id, config.case_insensitive_ids)) {
+    return Err(duplicate_in_request(&new_link.link_id));
+  }
+
+  let control_key = match bcrypt::hash(&new_link.control_key, bcrypt::DEFAULT_COST) {
+    Ok(hash) => hash,
+    Err(_) => return Err(
+      ResponseData::new().error(
+        Status::InternalServerError,
+        ResponseErrorType::ControlKeyHashGenerationError,
+        format!("Could not generate bcrypt hash of control key for link with ID '{}'.", new_link.link_id),
+        None
+      )
+    )
+  };
+  let password = super::utils::hash_password::<()>(&link.password)?;
+
+  let mut new_link_db = models::NewLink {
+    link_id: new_link.link_id.clone(),
+    control_key: control_key,
+    target: new_link.target.clone(),
+    expires_at: new_link.expires_at,
+    max_visits: new_link.max_visits,
+    password: password,
+    owner_id: user_id,
+    target_host: utils::target_host(&new_link.target),
+    link_key: utils::link_key(&new_link.link_id, config.case_insensitive_ids)
+  };
+
+  while link.link_id.is_none() && (link_ids.contains(&new_link_db.link_key) || custom_ids.contains(&new_link_db.link_key)) {
+    super::utils::reassign_link_id(&mut new_link, &mut new_link_db, ids, config);
+  }
+
+  Ok((new_link, new_link_db))
+}
+
+fn aborted<T: Serialize>() -> ResponseData<T> {
+  ResponseData::new().error(
+    Status::FailedDependency,
+    ResponseErrorType::BulkRequestError,
+    String::from("Link has not been added, because another request of atomic bulk request has failed."),
+    None
+  )
+}
+
+pub fn add_links_batch(links: Vec<requests::NewLink>, atomic: bool, user_id: Option<i64>, ids: &LinkIdGenerator, store: &dyn LinkStore, config: &Config) -> Response<successes::BulkResult<successes::NewLinkResult>> {
+  let res_data = ResponseData::<successes::BulkResult<successes::NewLinkResult>>::new();
+  let mut link_ids: HashSet<String> = HashSet::new();
+  let mut prepared: Vec<Result<(successes::NewLinkResult, models::NewLink), ResponseData<()>>> = Vec::new();
+  let custom_ids: HashSet<String> = links.iter()
+    .filter_map(|link| link.link_id.as_ref())
+    .map(|link_id| utils::link_key(link_id, config.case_insensitive_ids))
+    .collect();
+
+  for link in links.iter() {
+    let result = prepare_link(link, &link_ids, &custom_ids, user_id, ids, config);
+
+    if let Ok((_, new_link_db)) = &result {
+      link_ids.insert(new_link_db.link_key.clone());
+    }
+    prepared.push(result);
+  }
+
+  let invalid = prepared.iter().any(|result| result.is_err());
+  let mut results: Vec<successes::BulkItemResult<successes::NewLinkResult>> = Vec::new();
+
+  if atomic && invalid {
+    for (i, result) in prepared.into_iter().enumerate() {
+      let item = match result {
+        Ok(_) => aborted(),
+        Err(e) => e.transform(None)
+      };
+
+      results.push(successes::BulkItemResult::new(i as u32 + 1, item));
+    }
+  } else {
+    let mut new_links: Vec<successes::NewLinkResult> = Vec::new();
+    let mut new_links_db: Vec<models::NewLink> = Vec::new();
+    let mut auto_ids: Vec<bool> = Vec::new();
+
+    for (result, link) in prepared.iter().zip(links.iter()) {
+      if let Ok((new_link, new_link_db)) = result {
+        new_links.push(new_link.clone());
+        new_links_db.push(new_link_db.clone());
+        auto_ids.push(link.link_id.is_none());
+      }
+    }
+
+    let mut store_results = match super::utils::create_links(&mut new_links, &mut new_links_db, &auto_ids, atomic, ids, store, config) {
+      Ok(store_results) => store_results.into_iter(),
       Err(_) => {
-        res_data = errors::Errors::database_pool(res_data);
+        return res_data
+          .error(
+            Status::InternalServerError,
+            ResponseErrorType::DatabaseError,
+            String::from("Could not add links to database!"),
+            None
+          )
+          .to_response();
       }
+    };
+    let rolled_back = atomic && store_results.as_slice().iter().any(|result| result.is_err());
+    let mut new_links = new_links.into_iter();
+
+    for (i, result) in prepared.into_iter().enumerate() {
+      let item = match result {
+        Ok(_) => match (new_links.next(), store_results.next()) {
+          (Some(_), Some(Ok(()))) if rolled_back => aborted(),
+          (Some(new_link), Some(Ok(()))) => ResponseData::new().success(Status::Created, Some(new_link)),
+          (Some(new_link), Some(Err(e))) => errors::Errors::store_error(
+            ResponseData::new(),
+            &e,
+            &new_link.link_id,
+            String::from("Could not add link to database!")
+          ),
+          _ => aborted()
+        },
+        Err(e) => e.transform(None)
+      };
+
+      results.push(successes::BulkItemResult::new(i as u32 + 1, item));
     }
   }
 
+  let failed = results.iter()
+    .filter(|result| result.result.error_type.is_some())
+    .count();
+  let succeeded = results.len() - failed;
+
   res_data
+    .success(
+      match failed {
+        0 => Status::Ok,
+        _ => Status::MultiStatus
+      },
+      Some(
+        successes::BulkResult {
+          atomic: atomic,
+          succeeded: succeeded,
+          failed: failed,
+          results: results
+        }
+      )
+    )
     .to_response()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::store::memory::MemoryLinkStore;
+  use crate::store::normalized::NormalizedLinkStore;
+
+  fn store_link(store: &dyn LinkStore, link_id: &str) {
+    store.create(&models::NewLink {
+      link_id: String::from(link_id),
+      control_key: bcrypt::hash("secret", 4).unwrap(),
+      target: String::from("https://example.com/"),
+      expires_at: None,
+      max_visits: None,
+      password: None,
+      owner_id: None,
+      target_host: Some(String::from("example.com")),
+      link_key: String::from(link_id)
+    }).unwrap();
+  }
+
+  fn edit(link_id: &str, new_link_id: Option<&str>, target: Option<&str>) -> requests::EditLink {
+    requests::EditLink {
+      link_id: String::from(link_id),
+      new_link_id: new_link_id.map(String::from),
+      target: target.map(String::from),
+      expires_at: None,
+      max_visits: None,
+      control_key: Some(String::from("secret"))
+    }
+  }
+
+  #[test]
+  fn edit_links_rolls_back_on_store_error() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+
+    for link_id in ["first", "second", "third"] {
+      store_link(&store, link_id);
+    }
+
+    let links = vec![
+      edit("first", None, Some("https://other.example.org/")),
+      edit("second", Some("third"), None)
+    ];
+    let response = edit_links(links, None, &store, &config);
+
+    assert_eq!(response.status, Status::Conflict);
+    assert!(matches!(response.error_data, Some(errors::Errors::BulkRequestError { request_number: 2, .. })));
+    assert_eq!(store.get(&String::from("first")).unwrap().unwrap().target, "https://example.com/");
+    assert!(store.exists(&String::from("second")).unwrap());
+  }
+
+  #[test]
+  fn edit_links_rejects_repeated_new_link_id() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+
+    store_link(&store, "first");
+    store_link(&store, "second");
+
+    let links = vec![
+      edit("first", Some("renamed"), None),
+      edit("second", Some("renamed"), None)
+    ];
+    let response = edit_links(links, None, &store, &config);
+
+    assert_eq!(response.status, Status::Conflict);
+    assert!(matches!(response.error_data, Some(errors::Errors::BulkRequestError { request_number: 2, .. })));
+    assert!(store.exists(&String::from("first")).unwrap());
+    assert!(!store.exists(&String::from("renamed")).unwrap());
+  }
+
+  #[test]
+  fn delete_links_deletes_every_link() {
+    let store = MemoryLinkStore::new();
+
+    store_link(&store, "first");
+    store_link(&store, "second");
+
+    let links = ["first", "second"].iter()
+      .map(|link_id| requests::DeleteLink { link_id: String::from(*link_id), control_key: Some(String::from("secret")) })
+      .collect();
+    let response = delete_links(links, None, &store, &Config::default());
+
+    assert_eq!(response.status, Status::Ok);
+    assert!(!store.exists(&String::from("first")).unwrap());
+    assert!(!store.exists(&String::from("second")).unwrap());
+  }
+
+  fn new_link(link_id: Option<&str>, target: &str) -> requests::NewLink {
+    requests::NewLink {
+      link_id: link_id.map(String::from),
+      target: String::from(target),
+      expires_at: None,
+      max_visits: None,
+      password: None
+    }
+  }
+
+  fn item_statuses(response: &Response<successes::BulkResult<successes::NewLinkResult>>) -> Vec<Status> {
+    response.data.as_ref().unwrap().results.iter()
+      .map(|item| item.result.status)
+      .collect()
+  }
+
+  #[test]
+  fn add_links_batch_reports_item_errors() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+    let ids = crate::ids::generator(&config).unwrap();
+
+    store_link(&store, "taken");
+
+    let links = vec![
+      new_link(None, "https://example.com/"),
+      new_link(Some("fresh"), "javascript:alert(1)"),
+      new_link(Some("taken"), "https://example.com/"),
+      new_link(Some("twice"), "https://example.com/"),
+      new_link(Some("twice"), "https://example.com/")
+    ];
+    let response = add_links_batch(links, false, None, &ids, &store, &config);
+    let result = response.data.as_ref().unwrap();
+
+    assert_eq!(response.status, Status::MultiStatus);
+    assert_eq!((result.succeeded, result.failed), (2, 3));
+    assert_eq!(item_statuses(&response), vec![Status::Created, Status::BadRequest, Status::Conflict, Status::Created, Status::Conflict]);
+    assert!(store.exists(&String::from("twice")).unwrap());
+    assert!(!store.exists(&String::from("fresh")).unwrap());
+  }
+
+  #[test]
+  fn add_links_batch_regenerates_id_taken_by_custom_id() {
+    let store = MemoryLinkStore::new();
+    let config = Config {
+      id_generator: String::from("sequential"),
+      ..Default::default()
+    };
+    let ids = crate::ids::generator(&config).unwrap();
+
+    let links = vec![
+      new_link(None, "https://example.com/"),
+      new_link(Some("0"), "https://example.com/")
+    ];
+    let response = add_links_batch(links, true, None, &ids, &store, &config);
+    let result = response.data.as_ref().unwrap();
+
+    assert_eq!(response.status, Status::Ok);
+    assert_eq!(item_statuses(&response), vec![Status::Created, Status::Created]);
+    assert_ne!(result.results[0].result.data.as_ref().unwrap().link_id, "0");
+    assert!(store.exists(&String::from("0")).unwrap());
+  }
+
+  #[test]
+  fn add_links_batch_rolls_back_atomic_request() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+    let ids = crate::ids::generator(&config).unwrap();
+
+    store_link(&store, "taken");
+
+    let links = vec![
+      new_link(Some("fresh"), "https://example.com/"),
+      new_link(Some("taken"), "https://example.com/")
+    ];
+    let response = add_links_batch(links, true, None, &ids, &store, &config);
+    let result = response.data.as_ref().unwrap();
+
+    assert_eq!(response.status, Status::MultiStatus);
+    assert_eq!((result.succeeded, result.failed), (0, 2));
+    assert_eq!(item_statuses(&response), vec![Status::FailedDependency, Status::Conflict]);
+    assert!(!store.exists(&String::from("fresh")).unwrap());
+
+    let links = vec![
+      new_link(Some("fresh"), "https://example.com/"),
+      new_link(Some("other"), "javascript:alert(1)")
+    ];
+    let response = add_links_batch(links, true, None, &ids, &store, &config);
+
+    assert_eq!(item_statuses(&response), vec![Status::FailedDependency, Status::BadRequest]);
+    assert!(!store.exists(&String::from("fresh")).unwrap());
+  }
+
+  #[test]
+  fn delete_links_rejects_ids_differing_in_case() {
+    let store = NormalizedLinkStore::new(Box::new(MemoryLinkStore::new()), true);
+    let config = Config {
+      case_insensitive_ids: true,
+      ..Default::default()
+    };
+
+    store_link(&store, "first");
+
+    let links = ["first", "FIRST"].iter()
+      .map(|link_id| requests::DeleteLink { link_id: String::from(*link_id), control_key: Some(String::from("secret")) })
+      .collect();
+    let response = delete_links(links, None, &store, &config);
+
+    assert_eq!(response.status, Status::Conflict);
+    assert!(matches!(response.error_data, Some(errors::Errors::BulkRequestError { request_number: 2, .. })));
+    assert!(store.exists(&String::from("first")).unwrap());
+  }
+
+  #[test]
+  fn add_links_batch_grows_id_size_once_per_round() {
+    let store = MemoryLinkStore::new();
+    let config = Config {
+      id_alphabet: String::from("abcdefgh"),
+      max_auto_id_length: 1,
+      ..Default::default()
+    };
+    let ids = crate::ids::generator(&config).unwrap();
+
+    for link_id in ["a", "b", "c", "d", "e", "f", "g", "h"] {
+      store_link(&store, link_id);
+    }
+
+    let links = vec![
+      new_link(None, "https://example.com/"),
+      new_link(None, "https://example.com/")
+    ];
+    let response = add_links_batch(links, false, None, &ids, &store, &config);
+
+    assert_eq!(item_statuses(&response), vec![Status::Created, Status::Created]);
+    assert_eq!(ids.size(), 2);
+  }
+
+  #[test]
+  fn add_links_batch_rejects_custom_ids_differing_in_case() {
+    let config = Config {
+      case_insensitive_ids: true,
+      ..Default::default()
+    };
+    let store = NormalizedLinkStore::new(Box::new(MemoryLinkStore::new()), true);
+    let ids = crate::ids::generator(&config).unwrap();
+
+    let links = vec![
+      new_link(Some("MyLink"), "https://example.com/"),
+      new_link(Some("MYLINK"), "https://example.com/")
+    ];
+    let response = add_links_batch(links, false, None, &ids, &store, &config);
+
+    assert_eq!(item_statuses(&response), vec![Status::Created, Status::Conflict]);
+    assert_eq!(store.get(&String::from("mylink")).unwrap().unwrap().link_id, "MyLink");
+  }
 }
\ No newline at end of file
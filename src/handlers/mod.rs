@@ -1,3 +1,5 @@
 pub mod root;
 pub mod bulk;
+pub mod users;
+pub mod admin;
 pub mod utils;
\ No newline at end of file
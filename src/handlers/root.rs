@@ -1,156 +1,202 @@
-use rocket::{State};
-use rocket::response::Redirect;
+use rocket::response::{Redirect, content::RawHtml};
 use rocket::http::Status;
 use rocket::serde::json::{Json};
-use diesel::prelude::*;
+use nanoid::nanoid;
 use crate::responses::*;
 use crate::models;
-//use crate::guards;
-use crate::fairings::database::Pool;
+use crate::utils;
+use crate::guards;
+use crate::store::LinkStore;
 use crate::config::Config;
+use crate::ids::LinkIdGenerator;
 use crate::requests;
 
-pub fn access_link(link_id: String, db: &State<Pool>) -> Result<Redirect, Response<()>> {
-  let res_data = ResponseData::<()>::new();
+const TOP_REFERRERS_COUNT: i64 = 10;
 
-  match db.get() {
-    Ok(mut pool) => {
-      use crate::schema::links;
+fn unlock_form(status: Status, error_message: Option<&str>) -> AccessLinkResponse {
+  let error_message = match error_message {
+    Some(error_message) => format!("<p><strong>{}</strong></p>", error_message),
+    None => String::new()
+  };
 
-      let conn = &mut *pool;
+  AccessLinkResponse::Unlock((status, RawHtml(format!(r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>Protected link</title>
+  </head>
+  <body>
+    <form method="post" action="">
+      <p>This link is password protected. Enter the password to continue.</p>
+      {}
+      <input type="password" name="password" required autofocus>
+      <button type="submit">Unlock</button>
+    </form>
+  </body>
+</html>"#, error_message))))
+}
 
-      match links::table
-        .find(&link_id)
-        .limit(1)
-        .load::<models::Link>(conn) {
-          Ok(link) if link.len() > 0 => {
-            let target = link[0].target.clone();
+pub fn access_link(link_id: String, password: Option<&String>, visitor: &guards::visitor::Visitor, store: &dyn LinkStore) -> Result<AccessLinkResponse, Response<()>> {
+  let res_data = ResponseData::<()>::new();
 
-            Ok(Redirect::temporary(target))
-          },
-          Ok(_) => {
-            Err(
-              errors::Errors::link_id_not_found(res_data, &link_id)
-                .to_response()
-            )
-          },
-          Err(_) => Err(
-            res_data
-              .set_status(Status::InternalServerError)
-              .set_error_type(ResponseErrorType::DatabaseError)
-              .set_error_message(String::from("Could not fetch links from the database!"))
-              .to_response()
-          )
-        }
+  match store.get(&link_id) {
+    Ok(Some(link)) if link.disabled => {
+      Err(
+        errors::Errors::link_disabled(res_data, &link_id)
+          .to_response()
+      )
     },
-    Err(_) => {
+    Ok(Some(link)) if super::utils::is_expired(&link) => {
       Err(
-        errors::Errors::database_pool(res_data)
+        errors::Errors::link_expired(res_data, &link_id)
           .to_response()
       )
-    }
-  }
-}
+    },
+    Ok(Some(link)) => {
+      let target = link.target.clone();
 
-pub fn get_links(db: &State<Pool>, config: &State<Config>) -> (Status, Json<Response<Vec<successes::GetLink>>>) {
-  let res_data = ResponseData::<Vec<successes::GetLink>>::new();
-
-  match db.get() {
-    Ok(mut pool) => {
-      use crate::schema::links;
-      let conn = &mut *pool;
-
-      let base_url = config.base_url.clone();
-
-      match links::table
-        .order(links::added_at.desc())
-        .load::<models::Link>(conn) {
-          Ok(links) => {
-            let data = links.iter()
-              .map(|r| {
-                successes::GetLink {
-                  link_id: r.link_id.clone(),
-                  target: r.target.clone(),
-                  added_at: r.added_at.clone(),
-                  visit_count: r.visit_count.clone(),
-                  link: super::utils::build_link(&base_url, &r.link_id)
-                }
-              })
-              .collect::<Vec<successes::GetLink>>();
-
-            res_data.success(
-              Status::Ok,
-              Some(data)
-            )
-              .to_response()
-              .json_respond()
+      if let Some(hash) = &link.password {
+        match password {
+          Some(password) => match bcrypt::verify(password, hash) {
+            Ok(true) => (),
+            Ok(false) => {
+              return Ok(unlock_form(Status::Unauthorized, Some("Invalid password!")));
+            },
+            Err(_) => {
+              return Err(
+                res_data.error(
+                  Status::InternalServerError,
+                  ResponseErrorType::PasswordHashVerificationError,
+                  String::from("Could not verify validity of password!"),
+                  None
+                )
+                  .to_response()
+              );
+            }
           },
-          Err(_) => {
-            res_data
-              .error(
-                Status::InternalServerError,
-                ResponseErrorType::DatabaseError,
-                String::from("Could not fetch links from database!"),
-                None
-              )
-              .to_response()
-              .json_respond()
+          None => {
+            return Ok(unlock_form(Status::Ok, None));
           }
         }
+      }
+
+      match store.increment_visits(&link_id) {
+        Ok(false) => Err(
+          errors::Errors::link_expired(res_data, &link_id)
+            .to_response()
+        ),
+        Ok(true) => {
+          let new_visit = models::NewVisit {
+            link_id: link.link_id.clone(),
+            referrer: visitor.referrer.clone(),
+            user_agent: visitor.user_agent.clone(),
+            client_ip: visitor.client_ip.clone()
+          };
+
+          if store.record_visit(&new_visit).is_err() {
+            println!("Could not record visit of link with ID '{}'!", link_id);
+          }
+
+          match password {
+            Some(_) => Ok(AccessLinkResponse::Redirect(Redirect::to(target))),
+            None => Ok(AccessLinkResponse::Redirect(Redirect::temporary(target)))
+          }
+        },
+        Err(_) => Err(
+          res_data
+            .set_status(Status::InternalServerError)
+            .set_error_type(ResponseErrorType::DatabaseError)
+            .set_error_message(format!("Could not update visit count of link with ID '{}'!", link_id))
+            .to_response()
+        )
+      }
+    },
+    Ok(None) => {
+      Err(
+        errors::Errors::link_id_not_found(res_data, &link_id)
+          .to_response()
+      )
     },
-    Err(_) => {
-      errors::Errors::database_pool(res_data)
+    Err(_) => Err(
+      res_data
+        .set_status(Status::InternalServerError)
+        .set_error_type(ResponseErrorType::DatabaseError)
+        .set_error_message(String::from("Could not fetch links from the database!"))
         .to_response()
-        .json_respond()
-    }
+    )
   }
 }
 
-pub fn add_link(link: &requests::NewLink, db: &State<Pool>, config: &State<Config>) -> (Status, Json<Response<successes::NewLinkResult>>) {
+pub fn link_stats(link_id: &String, control_key: &Option<String>, user_id: Option<i64>, store: &dyn LinkStore) -> Result<successes::LinkStats, ResponseData<()>> {
   let res_data = ResponseData::new();
 
-  match super::utils::add_link(link, db, config) {
-    Ok(new_link) => {
+  match super::utils::authorize_link(link_id, control_key, user_id, store) {
+    Ok(()) => match store.visit_stats(link_id, TOP_REFERRERS_COUNT) {
+      Ok(stats) => Ok(successes::LinkStats {
+        link_id: link_id.clone(),
+        total_visits: stats.total,
+        daily: stats.daily.into_iter()
+          .map(|(date, visits)| successes::DailyVisits { date: date, visits: visits })
+          .collect(),
+        top_referrers: stats.top_referrers.into_iter()
+          .map(|(referrer, visits)| successes::ReferrerVisits { referrer: referrer, visits: visits })
+          .collect()
+      }),
+      Err(_) => Err(
+        res_data.error(
+          Status::InternalServerError,
+          ResponseErrorType::DatabaseError,
+          format!("Could not fetch visits of link with ID '{}' from database!", link_id),
+          None
+        )
+      )
+    },
+    Err(r) => Err(r)
+  }
+}
+
+pub fn add_link(link: &requests::NewLink, user_id: Option<i64>, ids: &LinkIdGenerator, store: &dyn LinkStore, config: &Config) -> (Status, Json<Response<successes::NewLinkResult>>) {
+  let res_data = ResponseData::new();
+
+  match super::utils::add_link(link, ids, config) {
+    Ok(mut new_link) => {
       let link_id = new_link.link_id.clone();
       let control_key = new_link.control_key.clone();
       let target = new_link.target.clone();
 
       match bcrypt::hash(&control_key, bcrypt::DEFAULT_COST) {
-        Ok(hash) => match db.get() {
-          Ok(mut pool) => {
-            use crate::schema::links;
-            let conn = &mut *pool;
-
-            let new_link_db = models::NewLink {
+        Ok(hash) => match super::utils::hash_password::<successes::NewLinkResult>(&link.password) {
+          Err(r) => r.to_response().json_respond(),
+          Ok(password) => {
+            let mut new_link_db = models::NewLink {
               link_id: link_id.clone(),
               control_key: hash,
-              target: target.clone()
+              target: target.clone(),
+              expires_at: new_link.expires_at,
+              max_visits: new_link.max_visits,
+              password: password,
+              owner_id: user_id,
+              target_host: utils::target_host(&target),
+              link_key: utils::link_key(&link_id, config.case_insensitive_ids)
             };
 
-            match diesel::insert_into(links::table)
-              .values(new_link_db)
-              .execute(conn) {
-                Ok(_) => {
-                  res_data.success(
-                    Status::Ok,
-                    Some(new_link)
-                  )
-                    .to_response()
-                    .json_respond()
-                },
-                Err(_) => {
-                  res_data.error(
-                    Status::InternalServerError,
-                    ResponseErrorType::DatabaseError,
-                    format!("Could not add link with ID '{}' to database!", link_id),
-                    None
-                  )
-                    .to_response()
-                    .json_respond()
-                }
+            match super::utils::create_link(&mut new_link, &mut new_link_db, link.link_id.is_none(), ids, store, config) {
+              Ok(()) => {
+                res_data.success(
+                  Status::Ok,
+                  Some(new_link)
+                )
+                  .to_response()
+                  .json_respond()
+              },
+              Err(e) => {
+                errors::Errors::store_error(res_data, &e, &new_link_db.link_id, format!("Could not add link with ID '{}' to database!", new_link_db.link_id))
+                  .to_response()
+                  .json_respond()
               }
-          },
-          Err(_) => errors::Errors::database_pool(res_data).to_response().json_respond()
+            }
+          }
         },
         Err(_) => {
           res_data.error(
@@ -173,68 +219,280 @@ pub fn add_link(link: &requests::NewLink, db: &State<Pool>, config: &State<Confi
   }
 }
 
-pub fn delete_link(link_id: &String, control_key: &String, db: &State<Pool>) -> Result<(), ResponseData<()>> {
+pub fn delete_link(link_id: &String, control_key: &Option<String>, user_id: Option<i64>, store: &dyn LinkStore) -> Result<(), ResponseData<()>> {
   let res_data = ResponseData::new();
 
-  match db.get() {
-    Ok(mut pool) => match super::utils::delete_link(link_id, control_key, db) {
-      Ok(()) => {
-        use crate::schema::links;
-        let conn = &mut *pool;
-
-        match diesel::delete(links::table)
-          .filter(links::link_id.eq(link_id))
-          .execute(conn) {
-            Ok(_) => Ok(()),
-            Err(_) => Err(
-              res_data.error(
-                Status::InternalServerError,
-                ResponseErrorType::DatabaseError,
-                format!("Could not delete link with ID '{}' from database!", link_id),
-                None
-              )
-            )
-          }
-      },
-      Err(r) => Err(r)
+  match super::utils::delete_link(link_id, control_key, user_id, store) {
+    Ok(()) => match store.delete(link_id) {
+      Ok(()) => Ok(()),
+      Err(e) => Err(
+        errors::Errors::store_error(res_data, &e, link_id, format!("Could not delete link with ID '{}' from database!", link_id))
+      )
     },
-    Err(_) => Err(errors::Errors::database_pool(res_data))
+    Err(r) => Err(r)
   }
 }
 
-pub fn edit_link(link_id: &String, control_key: &String, new_link_id: &Option<String>, new_target: &Option<String>, db: &State<Pool>, config: &State<Config>) -> Result<successes::EditLinkResult, ResponseData<()>> {
+pub fn edit_link(link: &requests::EditLink, user_id: Option<i64>, store: &dyn LinkStore, config: &Config) -> Result<successes::EditLinkResult, ResponseData<()>> {
   let res_data = ResponseData::new();
-  let base_url = config.base_url.clone();
-
-  match super::utils::edit_link(link_id, control_key, new_link_id, new_target, db, config) {
-    Ok((new_link_id, new_target)) => match db.get() {
-      Ok(mut pool) => {
-        use crate::schema::links;
-        let conn = &mut *pool;
-
-        match diesel::update(links::table)
-          .set((links::link_id.eq(&new_link_id), links::target.eq(&new_target)))
-          .filter(links::link_id.eq(link_id))
-          .execute(conn) {
-            Ok(_) => {
-              Ok(successes::EditLinkResult {
-                link_id: new_link_id.clone(),
-                target: new_target.clone(),
-                link: format!("{}/{}", base_url, new_link_id)
-              })
-            },
-            Err(_) => Err(
-              res_data.error(
-                Status::InternalServerError,
-                ResponseErrorType::DatabaseError,
-                format!("Could not update link with ID '{}' due to database error!", link_id),
-                None
-              )
+  let link_id = &link.link_id;
+
+  match super::utils::edit_link(link, user_id, store, config) {
+    Ok(edited) => {
+      let changes = super::utils::link_changes(&edited, config);
+
+      match store.update(link_id, &changes) {
+        Ok(()) => Ok(edited),
+        Err(e) => Err(
+          errors::Errors::store_error(res_data, &e, &edited.link_id, format!("Could not update link with ID '{}' due to database error!", link_id))
+        )
+      }
+    },
+    Err(r) => Err(r)
+  }
+}
+
+pub fn rotate_control_key(link_id: &String, control_key: &Option<String>, user_id: Option<i64>, store: &dyn LinkStore) -> Result<successes::RotateControlKeyResult, ResponseData<()>> {
+  let res_data = ResponseData::new();
+
+  match super::utils::authorize_link(link_id, control_key, user_id, store) {
+    Ok(()) => {
+      let new_control_key = nanoid!(24);
+
+      match bcrypt::hash(&new_control_key, bcrypt::DEFAULT_COST) {
+        Ok(hash) => {
+          let changes = models::LinkChanges {
+            control_key: Some(hash),
+            ..Default::default()
+          };
+
+          match store.update(link_id, &changes) {
+            Ok(()) => Ok(successes::RotateControlKeyResult {
+              link_id: link_id.clone(),
+              control_key: new_control_key
+            }),
+            Err(e) => Err(
+              errors::Errors::store_error(res_data, &e, link_id, format!("Could not update control key of link with ID '{}' due to database error!", link_id))
             )
           }
-      },
-      Err(_) => Err(errors::Errors::database_pool(res_data))
+        },
+        Err(_) => Err(
+          res_data.error(
+            Status::InternalServerError,
+            ResponseErrorType::ControlKeyHashGenerationError,
+            String::from("Could not generate bcrypt hash for new control key!"),
+            None
+          )
+        )
+      }
     },
     Err(r) => Err(r)
   }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ids;
+  use crate::store::memory::MemoryLinkStore;
+
+  fn visitor() -> guards::visitor::Visitor {
+    guards::visitor::Visitor {
+      referrer: Some(String::from("https://referrer.example")),
+      user_agent: None,
+      client_ip: None
+    }
+  }
+
+  fn new_link(link_id: Option<&str>, max_visits: Option<i32>) -> requests::NewLink {
+    requests::NewLink {
+      link_id: link_id.map(String::from),
+      target: String::from("https://example.com/page"),
+      expires_at: None,
+      max_visits: max_visits,
+      password: None
+    }
+  }
+
+  fn create(link: &requests::NewLink, store: &dyn LinkStore, config: &Config) -> successes::NewLinkResult {
+    let ids = ids::generator(config).unwrap();
+    let (status, response) = add_link(link, None, &ids, store, config);
+
+    assert_eq!(status, Status::Ok);
+    response.into_inner().data.unwrap()
+  }
+
+  #[test]
+  fn add_link_stores_link() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+
+    let created = create(&new_link(Some("example"), None), &store, &config);
+    let link = store.get(&String::from("example")).unwrap().unwrap();
+
+    assert_eq!(created.link_id, "example");
+    assert_eq!(link.target, "https://example.com/page");
+    assert_eq!(link.target_host, Some(String::from("example.com")));
+    assert!(bcrypt::verify(&created.control_key, &link.control_key).unwrap());
+  }
+
+  #[test]
+  fn add_link_generates_id() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+
+    let created = create(&new_link(None, None), &store, &config);
+
+    assert_eq!(created.link_id.chars().count(), config.max_auto_id_length);
+    assert!(store.exists(&created.link_id).unwrap());
+  }
+
+  #[test]
+  fn add_link_rejects_duplicate_id() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+    let ids = ids::generator(&config).unwrap();
+
+    create(&new_link(Some("example"), None), &store, &config);
+    let (status, response) = add_link(&new_link(Some("example"), None), None, &ids, &store, &config);
+
+    assert_eq!(status, Status::Conflict);
+    assert!(matches!(response.into_inner().error_type, Some(ResponseErrorType::DuplicateIdError)));
+  }
+
+  #[test]
+  fn access_link_redirects_and_records_visit() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+    let link_id = String::from("example");
+
+    create(&new_link(Some("example"), None), &store, &config);
+
+    assert!(matches!(access_link(link_id.clone(), None, &visitor(), &store), Ok(AccessLinkResponse::Redirect(_))));
+
+    let link = store.get(&link_id).unwrap().unwrap();
+    let stats = store.visit_stats(&link_id, TOP_REFERRERS_COUNT).unwrap();
+
+    assert_eq!(link.visit_count, 1);
+    assert_eq!(stats.total, 1);
+    assert_eq!(stats.top_referrers, vec![(Some(String::from("https://referrer.example")), 1)]);
+  }
+
+  #[test]
+  fn link_stats_reports_visits() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+    let link_id = String::from("example");
+
+    let created = create(&new_link(Some("example"), None), &store, &config);
+
+    for _ in 0..3 {
+      access_link(link_id.clone(), None, &visitor(), &store).ok();
+    }
+
+    let stats = link_stats(&link_id, &Some(created.control_key), None, &store).unwrap();
+
+    assert_eq!(stats.link_id, "example");
+    assert_eq!(stats.total_visits, 3);
+    assert_eq!(stats.daily.len(), 1);
+    assert_eq!(stats.daily[0].visits, 3);
+    assert_eq!(stats.top_referrers.len(), 1);
+    assert_eq!(stats.top_referrers[0].referrer, Some(String::from("https://referrer.example")));
+    assert!(link_stats(&link_id, &Some(String::from("invalid")), None, &store).is_err());
+  }
+
+  #[test]
+  fn access_link_rejects_unknown_link() {
+    let store = MemoryLinkStore::new();
+
+    match access_link(String::from("missing"), None, &visitor(), &store) {
+      Err(response) => assert_eq!(response.status, Status::NotFound),
+      Ok(_) => panic!("Unknown link should not be accessible!")
+    }
+  }
+
+  #[test]
+  fn access_link_expires_after_max_visits() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+    let link_id = String::from("example");
+
+    create(&new_link(Some("example"), Some(1)), &store, &config);
+
+    assert!(access_link(link_id.clone(), None, &visitor(), &store).is_ok());
+
+    match access_link(link_id.clone(), None, &visitor(), &store) {
+      Err(response) => {
+        assert_eq!(response.status, Status::Gone);
+        assert!(matches!(response.error_type, Some(ResponseErrorType::LinkExpiredError)));
+      },
+      Ok(_) => panic!("Expired link should not be accessible!")
+    }
+    assert_eq!(store.get(&link_id).unwrap().unwrap().visit_count, 1);
+  }
+
+  #[test]
+  fn edit_link_updates_stored_link() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+
+    let created = create(&new_link(Some("example"), None), &store, &config);
+    let edit = requests::EditLink {
+      link_id: String::from("example"),
+      new_link_id: Some(String::from("renamed")),
+      target: Some(String::from("https://other.example.org/")),
+      expires_at: None,
+      max_visits: Some(Some(5)),
+      control_key: Some(created.control_key)
+    };
+
+    let edited = edit_link(&edit, None, &store, &config).unwrap();
+    let link = store.get(&String::from("renamed")).unwrap().unwrap();
+
+    assert_eq!(edited.link_id, "renamed");
+    assert!(store.get(&String::from("example")).unwrap().is_none());
+    assert_eq!(link.target, "https://other.example.org/");
+    assert_eq!(link.target_host, Some(String::from("other.example.org")));
+    assert_eq!(link.max_visits, Some(5));
+  }
+
+  #[test]
+  fn rotate_control_key_replaces_old_key() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+    let link_id = String::from("example");
+
+    let created = create(&new_link(Some("example"), None), &store, &config);
+    let rotated = rotate_control_key(&link_id, &Some(created.control_key.clone()), None, &store).unwrap();
+    let link = store.get(&link_id).unwrap().unwrap();
+
+    assert_eq!(rotated.link_id, "example");
+    assert_ne!(rotated.control_key, created.control_key);
+    assert!(bcrypt::verify(&rotated.control_key, &link.control_key).unwrap());
+    assert!(!bcrypt::verify(&created.control_key, &link.control_key).unwrap());
+    assert!(delete_link(&link_id, &Some(created.control_key), None, &store).is_err());
+    assert!(delete_link(&link_id, &Some(rotated.control_key), None, &store).is_ok());
+  }
+
+  #[test]
+  fn edit_link_rejects_invalid_control_key() {
+    let store = MemoryLinkStore::new();
+    let config = Config::default();
+
+    create(&new_link(Some("example"), None), &store, &config);
+    let edit = requests::EditLink {
+      link_id: String::from("example"),
+      new_link_id: None,
+      target: Some(String::from("https://other.example.org/")),
+      expires_at: None,
+      max_visits: None,
+      control_key: Some(String::from("invalid"))
+    };
+
+    match edit_link(&edit, None, &store, &config) {
+      Err(r) => assert!(matches!(r.get_error_type(), Some(ResponseErrorType::InvalidControlKeyError))),
+      Ok(_) => panic!("Link should not be editable with invalid control key!")
+    }
+    assert_eq!(store.get(&String::from("example")).unwrap().unwrap().target, "https://example.com/page");
+  }
 }
\ No newline at end of file
@@ -0,0 +1,212 @@
+use rocket::{http::Status, State};
+use diesel::prelude::*;
+use nanoid::nanoid;
+use crate::requests;
+use crate::responses::*;
+use crate::config::Config;
+use crate::fairings::database::{Pool, DbConnection};
+use crate::models;
+use crate::utils;
+
+const MAX_USERNAME_LENGTH: usize = 255;
+
+fn generate_api_key() -> (String, String, successes::NewApiKeyResult) {
+  let key_id = nanoid!(16);
+  let secret = nanoid!(32);
+
+  (
+    key_id.clone(),
+    utils::hash_api_key(&secret),
+    successes::NewApiKeyResult {
+      key_id: key_id.clone(),
+      api_key: format!("{}.{}", key_id, secret)
+    }
+  )
+}
+
+fn insert_user(username: &String, key_id: &String, key_hash: &String, conn: &mut DbConnection) -> Result<i64, diesel::result::Error> {
+  use crate::schema::{users, api_keys};
+
+  diesel::insert_into(users::table)
+    .values(models::NewUser { username: username.clone() })
+    .execute(conn)?;
+
+  let user_id = users::table
+    .select(users::user_id)
+    .filter(users::username.eq(username))
+    .first::<i64>(conn)?;
+
+  diesel::insert_into(api_keys::table)
+    .values(models::NewApiKey {
+      key_id: key_id.clone(),
+      user_id: user_id,
+      key_hash: key_hash.clone()
+    })
+    .execute(conn)?;
+
+  Ok(user_id)
+}
+
+pub fn register(user: &requests::NewUser, db: &State<Pool>, config: &State<Config>) -> Result<successes::NewUserResult, ResponseData<()>> {
+  let res_data = ResponseData::new();
+  let username = user.username.trim().to_string();
+
+  if !config.allow_registration {
+    return Err(
+      res_data.error(
+        Status::Forbidden,
+        ResponseErrorType::RegistrationDisabledError,
+        String::from("Registration of new users is disabled!"),
+        None
+      )
+    );
+  }
+
+  if username.is_empty() || username.len() > MAX_USERNAME_LENGTH {
+    return Err(
+      res_data.error(
+        Status::BadRequest,
+        ResponseErrorType::ValidationError,
+        format!("Username has to be between 1 and {} characters long!", MAX_USERNAME_LENGTH),
+        None
+      )
+    );
+  }
+
+  match db.get() {
+    Ok(mut pool) => {
+      use crate::schema::users;
+      let conn = &mut *pool;
+
+      match users::table
+        .filter(users::username.eq(&username))
+        .count()
+        .get_result::<i64>(conn) {
+          Ok(0) => {
+            let (key_id, key_hash, key) = generate_api_key();
+
+            match conn.transaction(|conn| insert_user(&username, &key_id, &key_hash, conn)) {
+              Ok(user_id) => Ok(successes::NewUserResult {
+                user_id: user_id,
+                username: username,
+                key_id: key.key_id,
+                api_key: key.api_key
+              }),
+              Err(diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _)) => Err(
+                res_data.error(
+                  Status::Conflict,
+                  ResponseErrorType::DuplicateUsernameError,
+                  format!("User '{}' already exists!", username),
+                  None
+                )
+              ),
+              Err(_) => Err(
+                res_data.error(
+                  Status::InternalServerError,
+                  ResponseErrorType::DatabaseError,
+                  format!("Could not add user '{}' to database!", username),
+                  None
+                )
+              )
+            }
+          },
+          Ok(_) => Err(
+            res_data.error(
+              Status::Conflict,
+              ResponseErrorType::DuplicateUsernameError,
+              format!("User '{}' already exists!", username),
+              None
+            )
+          ),
+          Err(_) => Err(
+            res_data.error(
+              Status::InternalServerError,
+              ResponseErrorType::DatabaseError,
+              format!("Could not verify presence of user '{}'!", username),
+              None
+            )
+          )
+        }
+    },
+    Err(_) => Err(errors::Errors::database_pool(res_data))
+  }
+}
+
+pub fn create_api_key(user_id: Option<i64>, db: &State<Pool>) -> Result<successes::NewApiKeyResult, ResponseData<()>> {
+  let res_data = ResponseData::new();
+
+  let user_id = match user_id {
+    Some(user_id) => user_id,
+    None => return Err(errors::Errors::invalid_api_key(res_data))
+  };
+
+  let (key_id, key_hash, key) = generate_api_key();
+
+  match db.get() {
+    Ok(mut pool) => {
+      use crate::schema::api_keys;
+      let conn = &mut *pool;
+
+      let new_key = models::NewApiKey {
+        key_id: key_id,
+        user_id: user_id,
+        key_hash: key_hash
+      };
+
+      match diesel::insert_into(api_keys::table)
+        .values(new_key)
+        .execute(conn) {
+          Ok(_) => Ok(key),
+          Err(_) => Err(
+            res_data.error(
+              Status::InternalServerError,
+              ResponseErrorType::DatabaseError,
+              String::from("Could not add API key to database!"),
+              None
+            )
+          )
+        }
+    },
+    Err(_) => Err(errors::Errors::database_pool(res_data))
+  }
+}
+
+pub fn revoke_api_key(key_id: &String, user_id: Option<i64>, db: &State<Pool>) -> Result<(), ResponseData<()>> {
+  let res_data = ResponseData::new();
+
+  let user_id = match user_id {
+    Some(user_id) => user_id,
+    None => return Err(errors::Errors::invalid_api_key(res_data))
+  };
+
+  match db.get() {
+    Ok(mut pool) => {
+      use crate::schema::api_keys;
+      let conn = &mut *pool;
+
+      match diesel::delete(api_keys::table)
+        .filter(api_keys::key_id.eq(key_id))
+        .filter(api_keys::user_id.eq(user_id))
+        .execute(conn) {
+          Ok(0) => Err(
+            res_data.error(
+              Status::NotFound,
+              ResponseErrorType::InvalidApiKeyError,
+              format!("API key with ID '{}' not found!", key_id),
+              None
+            )
+          ),
+          Ok(_) => Ok(()),
+          Err(_) => Err(
+            res_data.error(
+              Status::InternalServerError,
+              ResponseErrorType::DatabaseError,
+              format!("Could not delete API key with ID '{}' from database!", key_id),
+              None
+            )
+          )
+        }
+    },
+    Err(_) => Err(errors::Errors::database_pool(res_data))
+  }
+}
\ No newline at end of file
@@ -0,0 +1,174 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::config::Config;
+
+pub mod random;
+pub mod sequential;
+pub mod words;
+
+pub const UNAMBIGUOUS_ALPHABET: &str = "23456789abcdefghijkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
+pub const UNAMBIGUOUS_LOWERCASE_ALPHABET: &str = "23456789abcdefghijkmnpqrstuvwxyz";
+const GROWTH_COLLISIONS: u32 = 3;
+const MAX_OCCUPANCY: f64 = 0.5;
+
+pub trait IdGenerator: Send + Sync {
+  fn generate(&self, size: usize) -> String;
+
+  fn grows(&self) -> bool {
+    true
+  }
+
+  fn keyspace(&self, _size: usize) -> f64 {
+    f64::INFINITY
+  }
+
+  fn seeds(&self) -> bool {
+    false
+  }
+
+  fn seed(&self, _link_id: &str) {}
+}
+
+pub struct LinkIdGenerator {
+  generator: Box<dyn IdGenerator>,
+  size: AtomicUsize,
+  max_size: usize
+}
+
+impl LinkIdGenerator {
+  pub fn new(generator: Box<dyn IdGenerator>, size: usize, max_size: usize) -> Self {
+    LinkIdGenerator {
+      generator: generator,
+      size: AtomicUsize::new(size.max(1)),
+      max_size: max_size.max(size)
+    }
+  }
+
+  pub fn generate(&self) -> String {
+    self.generator.generate(self.size())
+  }
+
+  pub fn size(&self) -> usize {
+    self.size.load(Ordering::Relaxed)
+  }
+
+  pub fn seeds(&self) -> bool {
+    self.generator.seeds()
+  }
+
+  pub fn seed(&self, link_id: &str) {
+    self.generator.seed(link_id);
+  }
+
+  pub fn fit(&self, links: u64) {
+    let mut size = self.size();
+
+    while size < self.max_size && links as f64 >= self.generator.keyspace(size) * MAX_OCCUPANCY {
+      size += 1;
+    }
+    if size > self.size() {
+      self.size.store(size, Ordering::Relaxed);
+      println!("Database contains {} links, auto-generated IDs have size {}.", links, size);
+    }
+  }
+
+  pub fn collided(&self, attempt: u32) {
+    if !attempt.is_multiple_of(GROWTH_COLLISIONS) || !self.generator.grows() {
+      return;
+    }
+
+    let size = self.size();
+
+    if size < self.max_size && self.size.compare_exchange(size, size + 1, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+      println!("Link ID keyspace is getting crowded, auto-generated IDs now have size {}.", size + 1);
+    }
+  }
+}
+
+fn alphabet(alphabet: &str, case_insensitive: bool) -> String {
+  match case_insensitive {
+    true => alphabet.to_lowercase().chars().fold(String::new(), |mut alphabet, c| {
+      if !alphabet.contains(c) {
+        alphabet.push(c);
+      }
+      alphabet
+    }),
+    false => alphabet.to_string()
+  }
+}
+
+pub fn generator(config: &Config) -> Result<LinkIdGenerator, String> {
+  match config.id_generator.as_str() {
+    "random" => Ok(LinkIdGenerator::new(
+      Box::new(random::RandomIdGenerator::new(&alphabet(&config.id_alphabet, config.case_insensitive_ids))?),
+      config.max_auto_id_length,
+      config.max_id_length
+    )),
+    "unambiguous" => Ok(LinkIdGenerator::new(
+      Box::new(random::RandomIdGenerator::new(match config.case_insensitive_ids {
+        true => UNAMBIGUOUS_LOWERCASE_ALPHABET,
+        false => UNAMBIGUOUS_ALPHABET
+      })?),
+      config.max_auto_id_length,
+      config.max_id_length
+    )),
+    "sequential" => Ok(LinkIdGenerator::new(
+      Box::new(sequential::SequentialIdGenerator::new(config.case_insensitive_ids)),
+      1,
+      1
+    )),
+    "words" => Ok(LinkIdGenerator::new(
+      Box::new(words::WordsIdGenerator::new()),
+      config.id_word_count,
+      config.max_id_length / (words::MAX_WORD_LENGTH + 1)
+    )),
+    other => Err(format!("Unknown link ID generator '{}'!", other))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fit_grows_size_with_link_count() {
+    let config = Config {
+      id_alphabet: String::from("ab"),
+      max_auto_id_length: 2,
+      ..Default::default()
+    };
+    let ids = generator(&config).unwrap();
+
+    ids.fit(1);
+    assert_eq!(ids.size(), 2);
+    ids.fit(40);
+    assert_eq!(ids.size(), 7);
+    assert_eq!(ids.generate().chars().count(), 7);
+  }
+
+  #[test]
+  fn sequential_seed_starts_above_highest_id() {
+    let ids = generator(&Config { id_generator: String::from("sequential"), ..Default::default() }).unwrap();
+
+    for link_id in ["Z", "10", "custom-id", "b"] {
+      ids.seed(link_id);
+    }
+
+    assert_eq!(ids.generate(), "11");
+  }
+
+  #[test]
+  fn case_insensitive_ids_use_lowercase_alphabet() {
+    let config = Config {
+      id_generator: String::from("sequential"),
+      case_insensitive_ids: true,
+      ..Default::default()
+    };
+    let ids = generator(&config).unwrap();
+
+    ids.seed("Y");
+
+    assert_eq!(ids.generate(), "z");
+    assert_eq!(ids.generate(), "10");
+    assert_eq!(alphabet("aBcAb", true), "abc");
+  }
+}
\ No newline at end of file
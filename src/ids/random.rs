@@ -0,0 +1,34 @@
+use nanoid::nanoid;
+use super::IdGenerator;
+
+pub struct RandomIdGenerator {
+  alphabet: Vec<char>
+}
+
+impl RandomIdGenerator {
+  pub fn new(alphabet: &str) -> Result<Self, String> {
+    let chars: Vec<char> = alphabet.chars().collect();
+
+    if chars.len() < 2 {
+      return Err(String::from("Link ID alphabet has to contain at least two characters!"));
+    }
+    if let Some((i, c)) = chars.iter().enumerate().find(|(i, c)| chars[..*i].contains(*c)) {
+      return Err(format!("Link ID alphabet contains character '{}' more than once (at position {})!", c, i + 1));
+    }
+    if let Some(c) = chars.iter().find(|c| c.is_whitespace() || c.is_control() || **c == '/' || **c == '?' || **c == '#' || **c == '%') {
+      return Err(format!("Link ID alphabet cannot contain character '{}'!", c.escape_default()));
+    }
+
+    Ok(RandomIdGenerator { alphabet: chars })
+  }
+}
+
+impl IdGenerator for RandomIdGenerator {
+  fn generate(&self, size: usize) -> String {
+    nanoid!(size, &self.alphabet)
+  }
+
+  fn keyspace(&self, size: usize) -> f64 {
+    (self.alphabet.len() as f64).powi(size as i32)
+  }
+}
\ No newline at end of file
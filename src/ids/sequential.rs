@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use super::IdGenerator;
+
+const ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+pub fn encode(mut value: u64, base: u64) -> String {
+  let mut encoded: Vec<u8> = Vec::new();
+
+  loop {
+    encoded.push(ALPHABET[(value % base) as usize]);
+    value /= base;
+
+    if value == 0 {
+      break;
+    }
+  }
+  encoded.reverse();
+
+  String::from_utf8(encoded).unwrap()
+}
+
+pub fn decode(encoded: &str, base: u64) -> Option<u64> {
+  if encoded.is_empty() {
+    return None;
+  }
+
+  encoded.bytes().try_fold(0u64, |value, byte| {
+    let digit = ALPHABET[..base as usize].iter().position(|c| *c == byte)? as u64;
+
+    value.checked_mul(base)?.checked_add(digit)
+  })
+}
+
+pub struct SequentialIdGenerator {
+  counter: AtomicU64,
+  base: u64
+}
+
+impl SequentialIdGenerator {
+  pub fn new(case_insensitive: bool) -> Self {
+    SequentialIdGenerator {
+      counter: AtomicU64::new(0),
+      base: match case_insensitive {
+        true => 36,
+        false => 62
+      }
+    }
+  }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+  fn generate(&self, _size: usize) -> String {
+    encode(self.counter.fetch_add(1, Ordering::Relaxed), self.base)
+  }
+
+  fn grows(&self) -> bool {
+    false
+  }
+
+  fn seeds(&self) -> bool {
+    true
+  }
+
+  fn seed(&self, link_id: &str) {
+    let link_id = match self.base {
+      36 => link_id.to_lowercase(),
+      _ => link_id.to_string()
+    };
+
+    if let Some(value) = decode(&link_id, self.base) {
+      self.counter.fetch_max(value.saturating_add(1), Ordering::Relaxed);
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,47 @@
+use nanoid::rngs;
+use super::IdGenerator;
+
+pub const MAX_WORD_LENGTH: usize = 4;
+const SEPARATOR: &str = "-";
+
+const WORDS: [&str; 128] = [
+  "able", "acid", "aged", "area", "army", "away", "baby", "back",
+  "ball", "band", "bank", "base", "bath", "bear", "beat", "bell",
+  "belt", "best", "bird", "blue", "boat", "body", "bold", "bone",
+  "book", "boot", "born", "both", "bowl", "busy", "cake", "calm",
+  "camp", "card", "care", "case", "cash", "cast", "cave", "chef",
+  "city", "clay", "club", "coal", "coat", "code", "cold", "cook",
+  "cool", "copy", "corn", "cost", "crew", "crop", "dark", "data",
+  "dawn", "deal", "deep", "deer", "desk", "dial", "dock", "door",
+  "dove", "down", "draw", "drum", "duck", "dust", "east", "easy",
+  "edge", "even", "exit", "face", "fact", "fair", "farm", "fast",
+  "fern", "film", "fine", "fire", "firm", "fish", "flag", "flat",
+  "flow", "fold", "food", "foot", "fork", "form", "fort", "free",
+  "frog", "fuel", "full", "game", "gate", "gear", "gift", "glad",
+  "goal", "gold", "golf", "good", "gray", "grow", "hair", "half",
+  "hall", "hand", "hard", "harp", "hawk", "head", "heat", "herb",
+  "high", "hill", "home", "hope", "horn", "huge", "idea", "iron"
+];
+
+#[derive(Default)]
+pub struct WordsIdGenerator;
+
+impl WordsIdGenerator {
+  pub fn new() -> Self {
+    WordsIdGenerator
+  }
+}
+
+impl IdGenerator for WordsIdGenerator {
+  fn generate(&self, size: usize) -> String {
+    rngs::default(size)
+      .iter()
+      .map(|byte| WORDS[*byte as usize % WORDS.len()])
+      .collect::<Vec<&str>>()
+      .join(SEPARATOR)
+  }
+
+  fn keyspace(&self, size: usize) -> f64 {
+    (WORDS.len() as f64).powi(size as i32)
+  }
+}
\ No newline at end of file
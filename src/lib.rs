@@ -1,3 +1,5 @@
+#![allow(clippy::redundant_field_names, clippy::ptr_arg, clippy::result_large_err, clippy::new_without_default, clippy::should_implement_trait)]
+
 pub mod config;
 pub mod models;
 pub mod schema;
@@ -7,6 +9,8 @@ pub mod catchers;
 pub mod guards;
 pub mod fairings;
 pub mod handlers;
+pub mod store;
+pub mod ids;
 
 pub mod responses;
 pub mod requests;
@@ -1,36 +1,100 @@
+use figment::Figment;
 use figment::providers::{Format, Toml};
-use rocket::{self, launch, routes, fairing::AdHoc, catchers};
+use rocket::{self, routes, fairing::AdHoc, catchers, Rocket, Build};
 use link_shortener_api::fairings;
 use link_shortener_api::config::Config;
 use link_shortener_api::catchers;
 use link_shortener_api::routes;
 
-#[launch]
-fn rocket() -> _ {
-  let figment = rocket::config::Config::figment()
-    .join(Toml::file("Config.toml").nested());
+fn figment() -> Figment {
+  rocket::config::Config::figment()
+    .join(Toml::file("Config.toml").nested())
+}
 
+fn rocket(figment: Figment) -> Rocket<Build> {
   rocket::custom(figment)
     .attach(AdHoc::config::<Config>())
     .attach(fairings::database::DatabaseInitiator)
+    .attach(fairings::ids::LinkIdGeneratorInitiator)
+    .attach(fairings::proxy::TrustedProxiesInitiator)
     .attach(fairings::rate_limit::RateLimit)
+    .attach(fairings::rate_limit::RateLimitSweeper)
+    .attach(fairings::reaper::LinkReaper)
     .mount("/", routes![
       routes::root::get_get_links, 
+      routes::root::post_get_links,
       routes::root::post_add_link,
       routes::root::put_add_link, 
       routes::root::get_access_link, 
+      routes::root::post_access_link,
       routes::root::delete_delete_link,
       routes::root::patch_edit_link,
       routes::root::post_edit_link,
-      routes::root::get_check_id
+      routes::root::get_check_id,
+      routes::root::get_link_stats,
+      routes::root::post_rotate_control_key
     ])
     .register("/", catchers![
       catchers::root::invalid_request_data,
+      catchers::root::unauthorized,
       catchers::root::rate_limited,
       catchers::root::default_catcher
     ])
+    .mount("/", routes![
+      routes::users::post_register,
+      routes::users::post_api_keys,
+      routes::users::delete_api_keys
+    ])
+    .mount("/admin", routes![
+      routes::admin::get_links,
+      routes::admin::post_disable_link,
+      routes::admin::post_enable_link,
+      routes::admin::post_reset_visits,
+      routes::admin::delete_link,
+      routes::admin::get_rate_limit,
+      routes::admin::get_cache
+    ])
+    .register("/admin", catchers![
+      catchers::admin::unauthorized
+    ])
     .mount("/bulk", routes![
       routes::bulk::put_add_link,
-      routes::bulk::post_add_link
+      routes::bulk::post_add_link,
+      routes::bulk::delete_delete_link,
+      routes::bulk::post_edit_link,
+      routes::bulk::patch_edit_link
     ])
+}
+
+fn migrate_only(figment: Figment) {
+  match figment.extract::<Config>() {
+    Ok(config) => match fairings::database::migrate(&config.database_url) {
+      Ok(count) => {
+        println!("Applied {} pending migration(s).", count);
+      },
+      Err(message) => {
+        println!("{}", message);
+        std::process::exit(1);
+      }
+    },
+    Err(e) => {
+      println!("Could not load configuration: {}", e);
+      std::process::exit(1);
+    }
+  }
+}
+
+#[allow(clippy::result_large_err)]
+#[rocket::main]
+async fn main() -> Result<(), rocket::Error> {
+  let figment = figment();
+
+  if std::env::args().any(|arg| arg == "--migrate-only") {
+    migrate_only(figment);
+    return Ok(());
+  }
+
+  let _ = rocket(figment).launch().await?;
+
+  Ok(())
 }
\ No newline at end of file
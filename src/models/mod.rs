@@ -13,19 +13,104 @@ pub struct Link {
   #[serde(rename = "addedAt")]
   pub added_at: NaiveDateTime,
   #[serde(rename = "visitCount")]
-  pub visit_count: i32
+  pub visit_count: i32,
+  #[serde(rename = "expiresAt")]
+  pub expires_at: Option<NaiveDateTime>,
+  #[serde(rename = "maxVisits")]
+  pub max_visits: Option<i32>,
+  #[serde(skip_serializing)]
+  pub expired_at: Option<NaiveDateTime>,
+  #[serde(skip_serializing)]
+  pub password: Option<String>,
+  #[serde(rename = "ownerId")]
+  pub owner_id: Option<i64>,
+  pub disabled: bool,
+  #[serde(skip_serializing)]
+  pub target_host: Option<String>,
+  #[serde(skip_serializing)]
+  pub link_key: String
 }
 
-#[derive(Insertable)]
+#[derive(Insertable, Clone)]
 #[diesel(table_name = links)]
 pub struct NewLink {
   pub link_id: String,
   pub target: String,
-  pub control_key: String
+  pub control_key: String,
+  pub expires_at: Option<NaiveDateTime>,
+  pub max_visits: Option<i32>,
+  pub password: Option<String>,
+  pub owner_id: Option<i64>,
+  pub target_host: Option<String>,
+  pub link_key: String
 }
 
-#[derive(AsChangeset)]
+#[derive(AsChangeset, Default, Clone)]
 #[diesel(table_name = links)]
-pub struct UpdateVisitCountLinks {
-  pub visit_count: i32
+pub struct LinkChanges {
+  pub link_id: Option<String>,
+  pub target: Option<String>,
+  pub target_host: Option<Option<String>>,
+  pub control_key: Option<String>,
+  pub visit_count: Option<i32>,
+  pub expires_at: Option<Option<NaiveDateTime>>,
+  pub max_visits: Option<Option<i32>>,
+  pub disabled: Option<bool>,
+  pub link_key: Option<String>
+}
+
+
+#[derive(Queryable, Serialize, Clone)]
+pub struct Visit {
+  #[serde(rename = "visitId")]
+  pub visit_id: i64,
+  #[serde(rename = "linkId")]
+  pub link_id: String,
+  #[serde(rename = "visitedAt")]
+  pub visited_at: NaiveDateTime,
+  pub referrer: Option<String>,
+  #[serde(rename = "userAgent")]
+  pub user_agent: Option<String>,
+  #[serde(rename = "clientIp")]
+  pub client_ip: Option<String>
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = visits)]
+pub struct NewVisit {
+  pub link_id: String,
+  pub referrer: Option<String>,
+  pub user_agent: Option<String>,
+  pub client_ip: Option<String>
+}
+
+#[derive(Queryable, Serialize, Clone)]
+pub struct User {
+  #[serde(rename = "userId")]
+  pub user_id: i64,
+  pub username: String,
+  #[serde(rename = "createdAt")]
+  pub created_at: NaiveDateTime
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = users)]
+pub struct NewUser {
+  pub username: String
+}
+
+#[derive(Queryable, Clone)]
+pub struct ApiKey {
+  pub key_id: String,
+  pub user_id: i64,
+  pub key_hash: String,
+  pub created_at: NaiveDateTime
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = api_keys)]
+pub struct NewApiKey {
+  pub key_id: String,
+  pub user_id: i64,
+  pub key_hash: String
 }
\ No newline at end of file
@@ -1,10 +1,45 @@
 use serde::{Serialize, Deserialize};
+use rocket::{FromForm, FromFormField};
+use chrono::NaiveDateTime;
+use crate::utils;
 
 #[derive(Serialize, Deserialize)]
 pub struct NewLink {
   #[serde(rename = "linkId")]
   pub link_id: Option<String>,
-  pub target: String
+  pub target: String,
+  #[serde(rename = "expiresAt")]
+  pub expires_at: Option<NaiveDateTime>,
+  #[serde(rename = "maxVisits")]
+  pub max_visits: Option<i32>,
+  pub password: Option<String>
+}
+
+fn default_atomic() -> bool {
+  true
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BulkRequest<T> {
+  #[serde(default = "default_atomic")]
+  pub atomic: bool,
+  pub links: Vec<T>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BulkNewLinks {
+  Links(Vec<NewLink>),
+  Batch(BulkRequest<NewLink>)
+}
+
+impl BulkNewLinks {
+  pub fn item_count(&self) -> usize {
+    match self {
+      BulkNewLinks::Links(links) => links.len(),
+      BulkNewLinks::Batch(batch) => batch.links.len()
+    }
+  }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -14,14 +49,84 @@ pub struct EditLink {
   #[serde(rename = "newLinkId")]
   pub new_link_id: Option<String>,
   pub target: Option<String>,
+  #[serde(rename = "expiresAt")]
+  #[serde(default, deserialize_with = "utils::deserialize_nullable")]
+  pub expires_at: Option<Option<NaiveDateTime>>,
+  #[serde(rename = "maxVisits")]
+  #[serde(default, deserialize_with = "utils::deserialize_nullable")]
+  pub max_visits: Option<Option<i32>>,
   #[serde(rename = "controlKey")]
-  pub control_key: String
+  pub control_key: Option<String>
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct DeleteLink {
+  #[serde(rename = "linkId")]
+  pub link_id: String,
+  #[serde(rename = "controlKey")]
+  pub control_key: Option<String>
+}
+
+#[derive(FromForm)]
+pub struct UnlockLink {
+  pub password: String
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RotateControlKey {
+  #[serde(rename = "linkId")]
+  pub link_id: String,
+  #[serde(rename = "controlKey")]
+  pub control_key: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LinkCredentials {
   #[serde(rename = "linkId")]
   pub link_id: String,
   #[serde(rename = "controlKey")]
   pub control_key: String
+}
+
+#[derive(FromFormField, Clone, Copy)]
+pub enum LinksSortBy {
+  #[field(value = "addedAt")]
+  AddedAt,
+  #[field(value = "visitCount")]
+  VisitCount
+}
+
+#[derive(FromFormField, Clone, Copy)]
+pub enum SortOrder {
+  #[field(value = "asc")]
+  Asc,
+  #[field(value = "desc")]
+  Desc
+}
+
+#[derive(FromForm)]
+pub struct GetLinks {
+  pub limit: Option<i64>,
+  pub cursor: Option<String>,
+  pub offset: Option<i64>,
+  #[field(name = "sortBy")]
+  pub sort_by: Option<LinksSortBy>,
+  pub order: Option<SortOrder>,
+  pub domain: Option<String>,
+  pub search: Option<String>,
+  #[field(name = "createdAfter")]
+  pub created_after: Option<String>,
+  #[field(name = "createdBefore")]
+  pub created_before: Option<String>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NewUser {
+  pub username: String
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RevokeApiKey {
+  #[serde(rename = "keyId")]
+  pub key_id: String
 }
\ No newline at end of file
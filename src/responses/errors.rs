@@ -2,6 +2,7 @@ use serde::{Serialize};
 use super::*;
 use rocket::http::Status;
 use std::boxed::Box;
+use crate::store::StoreError;
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(untagged)]
@@ -25,11 +26,42 @@ pub enum Errors {
     request_error_data: Option<Box<Errors>>
   },
   LinkIdTooLongError {
+    rule: String,
     #[serde(rename = "providedIdLength")]
     provided_id_length: usize,
     #[serde(rename = "maxIdLength")]
     max_id_length: usize
   },
+  LinkIdTooShortError {
+    rule: String,
+    #[serde(rename = "providedIdLength")]
+    provided_id_length: usize,
+    #[serde(rename = "minIdLength")]
+    min_id_length: usize
+  },
+  LinkIdCharacterError {
+    rule: String,
+    #[serde(rename = "invalidCharacter")]
+    invalid_character: String,
+    #[serde(rename = "allowedCharacters")]
+    allowed_characters: String
+  },
+  TargetRejectedError {
+    reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scheme: Option<String>,
+    #[serde(rename = "allowedSchemes")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allowed_schemes: Option<Vec<String>>,
+    #[serde(rename = "baseUrl")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_url: Option<String>
+  },
+  LinkIdReservedError {
+    rule: String,
+    #[serde(rename = "reservedId")]
+    reserved_id: String
+  },
   NoError
 }
 
@@ -42,6 +74,26 @@ impl Errors {
 
     response_data
   }
+  pub fn missing_control_key<S: Serialize>(mut response_data: ResponseData<S>, link_id: &String) -> ResponseData<S> {
+    response_data = response_data.error(
+      Status::Unauthorized,
+      ResponseErrorType::InvalidControlKeyError,
+      format!("Control key for link with ID '{}' has to be provided!", link_id),
+      None
+    );
+
+    response_data
+  }
+  pub fn invalid_api_key<S: Serialize>(mut response_data: ResponseData<S>) -> ResponseData<S> {
+    response_data = response_data.error(
+      Status::Unauthorized,
+      ResponseErrorType::InvalidApiKeyError,
+      String::from("Valid API key has to be provided in 'Authorization' header!"),
+      None
+    );
+
+    response_data
+  }
   pub fn database_pool<S: Serialize>(mut response_data: ResponseData<S>) -> ResponseData<S> {
     response_data = response_data
       .set_status(Status::InternalServerError)
@@ -58,6 +110,26 @@ impl Errors {
 
     response_data
   }
+  pub fn link_expired<S: Serialize>(mut response_data: ResponseData<S>, link_id: &String) -> ResponseData<S> {
+    response_data = response_data.error(
+      Status::Gone,
+      ResponseErrorType::LinkExpiredError,
+      format!("Link with ID '{}' has expired!", link_id),
+      None
+    );
+
+    response_data
+  }
+  pub fn link_disabled<S: Serialize>(mut response_data: ResponseData<S>, link_id: &String) -> ResponseData<S> {
+    response_data = response_data.error(
+      Status::Forbidden,
+      ResponseErrorType::LinkDisabledError,
+      format!("Link with ID '{}' has been disabled!", link_id),
+      None
+    );
+
+    response_data
+  }
   pub fn duplicate_id<S: Serialize>(mut response_data: ResponseData<S>, link_id: &String) -> ResponseData<S> {
     response_data = response_data.error(
       Status::Conflict,
@@ -68,11 +140,95 @@ impl Errors {
 
     response_data
   }
+  pub fn store_error<S: Serialize>(mut response_data: ResponseData<S>, error: &StoreError, link_id: &String, error_message: String) -> ResponseData<S> {
+    response_data = match error {
+      StoreError::NotFound => Errors::link_id_not_found(response_data, link_id),
+      StoreError::Duplicate => Errors::duplicate_id(response_data, link_id),
+      StoreError::Database(_) => response_data.error(
+        Status::InternalServerError,
+        ResponseErrorType::DatabaseError,
+        error_message,
+        None
+      )
+    };
+
+    response_data
+  }
   pub fn target_invalid<S: Serialize>(mut response_data: ResponseData<S>, target: &String) -> ResponseData<S> {
-    response_data = response_data
-      .set_status(Status::BadRequest)
-      .set_error_type(ResponseErrorType::ValidationError)
-      .set_error_message(format!("Target '{}' is not a valid URL!", target));
+    response_data = response_data.error(
+      Status::BadRequest,
+      ResponseErrorType::ValidationError,
+      format!("Target '{}' is not a valid URL!", target),
+      Some(
+        Errors::TargetRejectedError {
+          reason: String::from("invalidUrl"),
+          scheme: None,
+          allowed_schemes: None,
+          base_url: None
+        }
+      )
+    );
+
+    response_data
+  }
+  pub fn target_scheme_not_allowed<S: Serialize>(mut response_data: ResponseData<S>, target: &String, scheme: &str, allowed_schemes: &Vec<String>) -> ResponseData<S> {
+    response_data = response_data.error(
+      Status::BadRequest,
+      ResponseErrorType::ValidationError,
+      format!("Target '{}' uses '{}' scheme, which is not allowed!", target, scheme),
+      Some(
+        Errors::TargetRejectedError {
+          reason: String::from("scheme"),
+          scheme: Some(scheme.to_string()),
+          allowed_schemes: Some(allowed_schemes.clone()),
+          base_url: None
+        }
+      )
+    );
+
+    response_data
+  }
+  pub fn target_userinfo<S: Serialize>(mut response_data: ResponseData<S>, target: &String) -> ResponseData<S> {
+    response_data = response_data.error(
+      Status::BadRequest,
+      ResponseErrorType::ValidationError,
+      format!("Target '{}' cannot contain credentials!", target),
+      Some(
+        Errors::TargetRejectedError {
+          reason: String::from("userinfo"),
+          scheme: None,
+          allowed_schemes: None,
+          base_url: None
+        }
+      )
+    );
+
+    response_data
+  }
+  pub fn target_self_reference<S: Serialize>(mut response_data: ResponseData<S>, target: &String, base_url: &String) -> ResponseData<S> {
+    response_data = response_data.error(
+      Status::BadRequest,
+      ResponseErrorType::ValidationError,
+      format!("Target '{}' points back at the link shortener!", target),
+      Some(
+        Errors::TargetRejectedError {
+          reason: String::from("selfReference"),
+          scheme: None,
+          allowed_schemes: None,
+          base_url: Some(base_url.clone())
+        }
+      )
+    );
+
+    response_data
+  }
+  pub fn invalid_date<S: Serialize>(mut response_data: ResponseData<S>, field: &str, date: &String) -> ResponseData<S> {
+    response_data = response_data.error(
+      Status::BadRequest,
+      ResponseErrorType::ValidationError,
+      format!("'{}' is not a valid date for '{}'!", date, field),
+      None
+    );
 
     response_data
   }
@@ -90,10 +246,7 @@ impl Errors {
           request_number: request_number, 
           request_error_type: error_type, 
           request_error_message: error_message, 
-          request_error_data: match error_data {
-            Some(data) => Some(Box::new(data)),
-            None => None
-          } 
+          request_error_data: error_data.map(Box::new)
         }
       )
     );
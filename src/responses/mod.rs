@@ -1,6 +1,8 @@
 use serde::{Serialize};
 use rocket::serde::json::{Json};
 use rocket::http::Status;
+use rocket::response::{Redirect, content::RawHtml};
+use rocket::Responder;
 
 pub mod errors;
 pub mod successes;
@@ -11,10 +13,18 @@ pub enum ResponseErrorType {
   DatabaseError,
   DuplicateIdError,
   InvalidControlKeyError,
+  InvalidApiKeyError,
+  DuplicateUsernameError,
+  RegistrationDisabledError,
   RateLimitedError,
   LinkNotFoundError,
+  LinkExpiredError,
+  LinkDisabledError,
+  InvalidAdminTokenError,
   ControlKeyHashGenerationError,
   ControlKeyHashVerificationError,
+  PasswordHashGenerationError,
+  PasswordHashVerificationError,
   BulkRequestExceedingSizeError,
   BulkRequestError,
   GetLinksError,
@@ -172,7 +182,7 @@ impl<S: Serialize> ResponseData<S> {
   }
 
   pub fn clone_status(&self) -> Status {
-    self.status.clone()
+    self.status
   }
   pub fn clone_error_type(&self) -> Option<ResponseErrorType> {
     self.error_type.clone()
@@ -181,10 +191,7 @@ impl<S: Serialize> ResponseData<S> {
     self.error_message.clone()
   }
   pub fn clone_error_data(&self) -> Option<errors::Errors> {
-    match &self.error_data {
-      Some(data) => Some(data.clone()),
-      None => None
-    }
+    self.error_data.clone()
   }
 
   pub fn success(mut self, status: Status, data: Option<S>) -> Self {
@@ -230,7 +237,7 @@ impl<S: Serialize> ResponseData<S> {
 impl<S: Serialize + Clone> ResponseData<S> {
   pub fn clone(&self) -> ResponseData<S> {
     ResponseData {
-      status: self.status.clone(),
+      status: self.status,
       status_type: self.status_type.clone(),
       data: self.data.clone(),
       error_type: self.error_type.clone(),
@@ -239,9 +246,20 @@ impl<S: Serialize + Clone> ResponseData<S> {
     }
   }
   pub fn clone_data(&self) -> Option<S> {
-    match &self.data {
-      Some(data) => Some(data.clone()),
-      None => None
-    }
+    self.data.clone()
   }
 }
+
+#[derive(Responder)]
+#[allow(clippy::large_enum_variant)]
+pub enum AccessLinkResponse {
+  Redirect(Redirect),
+  Unlock((Status, RawHtml<String>))
+}
+
+
+#[derive(Responder)]
+pub enum BulkAddLinksResponse {
+  Legacy((Status, Json<Response<Vec<successes::NewLinkResult>>>)),
+  Batch((Status, Json<Response<successes::BulkResult<successes::NewLinkResult>>>))
+}
\ No newline at end of file
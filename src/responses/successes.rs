@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use super::{Response, ResponseData};
 
 #[derive(Serialize, Deserialize)]
 pub struct GetLink {
@@ -9,9 +10,22 @@ pub struct GetLink {
   pub added_at: chrono::NaiveDateTime,
   #[serde(rename = "visitCount")]
   pub visit_count: i32,
+  #[serde(rename = "expiresAt")]
+  pub expires_at: Option<chrono::NaiveDateTime>,
+  #[serde(rename = "maxVisits")]
+  pub max_visits: Option<i32>,
+  pub disabled: bool,
   pub link: String
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct GetLinks {
+  pub links: Vec<GetLink>,
+  pub total: i64,
+  #[serde(rename = "nextCursor")]
+  pub next_cursor: Option<String>
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct NewLinkResult {
   #[serde(rename = "linkId")]
@@ -19,6 +33,12 @@ pub struct NewLinkResult {
   pub target: String,
   #[serde(rename = "controlKey")]
   pub control_key: String,
+  #[serde(rename = "expiresAt")]
+  pub expires_at: Option<chrono::NaiveDateTime>,
+  #[serde(rename = "maxVisits")]
+  pub max_visits: Option<i32>,
+  #[serde(rename = "passwordProtected")]
+  pub password_protected: bool,
   pub link: String
 }
 
@@ -27,5 +47,102 @@ pub struct EditLinkResult {
   #[serde(rename = "linkId")]
   pub link_id: String,
   pub target: String,
+  #[serde(rename = "expiresAt")]
+  pub expires_at: Option<chrono::NaiveDateTime>,
+  #[serde(rename = "maxVisits")]
+  pub max_visits: Option<i32>,
   pub link: String
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RotateControlKeyResult {
+  #[serde(rename = "linkId")]
+  pub link_id: String,
+  #[serde(rename = "controlKey")]
+  pub control_key: String
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DailyVisits {
+  pub date: chrono::NaiveDate,
+  pub visits: i64
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReferrerVisits {
+  pub referrer: Option<String>,
+  pub visits: i64
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LinkStats {
+  #[serde(rename = "linkId")]
+  pub link_id: String,
+  #[serde(rename = "totalVisits")]
+  pub total_visits: i64,
+  pub daily: Vec<DailyVisits>,
+  #[serde(rename = "topReferrers")]
+  pub top_referrers: Vec<ReferrerVisits>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NewApiKeyResult {
+  #[serde(rename = "keyId")]
+  pub key_id: String,
+  #[serde(rename = "apiKey")]
+  pub api_key: String
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NewUserResult {
+  #[serde(rename = "userId")]
+  pub user_id: i64,
+  pub username: String,
+  #[serde(rename = "keyId")]
+  pub key_id: String,
+  #[serde(rename = "apiKey")]
+  pub api_key: String
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RateLimitEntry {
+  pub ip: String,
+  pub policy: String,
+  pub requests: i64,
+  #[serde(rename = "resetAt")]
+  pub reset_at: chrono::NaiveDateTime
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CacheStats {
+  pub size: usize,
+  pub capacity: usize,
+  pub ttl: u64,
+  pub hits: u64,
+  pub misses: u64
+}
+
+#[derive(Serialize, Clone)]
+pub struct BulkItemResult<T: Serialize> {
+  #[serde(rename = "requestNumber")]
+  pub request_number: u32,
+  #[serde(flatten)]
+  pub result: Response<T>
+}
+
+impl<T: Serialize> BulkItemResult<T> {
+  pub fn new(request_number: u32, result: ResponseData<T>) -> Self {
+    BulkItemResult {
+      request_number: request_number,
+      result: result.to_response()
+    }
+  }
+}
+
+#[derive(Serialize, Clone)]
+pub struct BulkResult<T: Serialize> {
+  pub atomic: bool,
+  pub succeeded: usize,
+  pub failed: usize,
+  pub results: Vec<BulkItemResult<T>>
 }
\ No newline at end of file
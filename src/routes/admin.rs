@@ -0,0 +1,89 @@
+use std::sync::Arc;
+use rocket::{get, post, delete, State};
+use rocket::serde::json::Json;
+use rocket::http::Status;
+use crate::guards::admin::Admin;
+use crate::store::{Store, LinksScope, cache::LinkCache};
+use crate::fairings::rate_limit::RateLimitState;
+use crate::responses::*;
+use crate::requests;
+use crate::config::Config;
+use crate::handlers;
+
+fn respond_empty(result: Result<(), ResponseData<()>>) -> (Status, Json<Response<()>>) {
+  match result {
+    Ok(()) => {
+      ResponseData::new()
+        .success(Status::Ok, None)
+        .to_response()
+        .json_respond()
+    },
+    Err(r) => r.to_response().json_respond()
+  }
+}
+
+#[get("/links?<query..>")]
+pub fn get_links(query: requests::GetLinks, _admin: Admin, store: &State<Store>, config: &State<Config>) -> (Status, Json<Response<successes::GetLinks>>) {
+  let res_data = ResponseData::new();
+
+  match handlers::utils::get_links(&LinksScope::Public, &query, store.inner().as_ref(), config) {
+    Ok(links) => {
+      res_data.success(Status::Ok, Some(links))
+        .to_response()
+        .json_respond()
+    },
+    Err(r) => {
+      r
+        .transform(None)
+        .to_response()
+        .json_respond()
+    }
+  }
+}
+
+#[post("/links/<link_id>/disable")]
+pub fn post_disable_link(link_id: String, _admin: Admin, store: &State<Store>) -> (Status, Json<Response<()>>) {
+  respond_empty(handlers::admin::set_disabled(&link_id, true, store.inner().as_ref()))
+}
+
+#[post("/links/<link_id>/enable")]
+pub fn post_enable_link(link_id: String, _admin: Admin, store: &State<Store>) -> (Status, Json<Response<()>>) {
+  respond_empty(handlers::admin::set_disabled(&link_id, false, store.inner().as_ref()))
+}
+
+#[post("/links/<link_id>/reset-visits")]
+pub fn post_reset_visits(link_id: String, _admin: Admin, store: &State<Store>) -> (Status, Json<Response<()>>) {
+  respond_empty(handlers::admin::reset_visits(&link_id, store.inner().as_ref()))
+}
+
+#[delete("/links/<link_id>")]
+pub fn delete_link(link_id: String, _admin: Admin, store: &State<Store>) -> (Status, Json<Response<()>>) {
+  respond_empty(handlers::admin::delete_link(&link_id, store.inner().as_ref()))
+}
+
+#[get("/rate-limit")]
+pub fn get_rate_limit(_admin: Admin, state: &State<RateLimitState>, config: &State<Config>) -> (Status, Json<Response<Vec<successes::RateLimitEntry>>>) {
+  let res_data = ResponseData::new();
+
+  match handlers::admin::rate_limit_entries(state, config) {
+    Ok(entries) => {
+      res_data.success(Status::Ok, Some(entries))
+        .to_response()
+        .json_respond()
+    },
+    Err(r) => {
+      r
+        .transform(None)
+        .to_response()
+        .json_respond()
+    }
+  }
+}
+
+#[get("/cache")]
+pub fn get_cache(_admin: Admin, cache: &State<Arc<LinkCache>>) -> (Status, Json<Response<successes::CacheStats>>) {
+  ResponseData::new()
+    .success(Status::Ok, Some(handlers::admin::cache_stats(cache)))
+    .to_response()
+    .json_respond()
+}
\ No newline at end of file
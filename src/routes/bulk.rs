@@ -1,24 +1,110 @@
-use rocket::{post, put, State};
+use rocket::{post, put, patch, delete, State};
 use rocket::serde::json::Json;
 use rocket::http::Status;
-use crate::guards::rate_limit::RateLimit;
-use crate::fairings::database::Pool;
+use crate::guards::rate_limit::BulkRateLimit;
+use crate::guards::auth::Auth;
+use crate::store::Store;
 use crate::responses::*;
 use crate::requests;
 use crate::config::Config;
+use crate::ids::LinkIdGenerator;
 use crate::handlers;
 
 #[put("/add-link", data = "<links>")]
-pub fn put_add_link(links: Json<Vec<requests::NewLink>>, db: &State<Pool>, _rl: RateLimit, config: &State<Config>) -> (Status, Json<Response<Vec<successes::NewLinkResult>>>) {
+pub fn put_add_link(links: Json<requests::BulkNewLinks>, auth: Auth, store: &State<Store>, ids: &State<LinkIdGenerator>, rl: BulkRateLimit<'_>, config: &State<Config>) -> BulkAddLinksResponse {
   let links = links.into_inner();
 
-  handlers::bulk::add_links(links, db, config)
-    .json_respond()
+  match rl.charge(links.item_count()) {
+    Ok(()) => match links {
+      requests::BulkNewLinks::Links(links) => BulkAddLinksResponse::Legacy(
+        handlers::bulk::add_links(links, auth.user_id(), ids, store.inner().as_ref(), config)
+          .json_respond()
+      ),
+      requests::BulkNewLinks::Batch(batch) => BulkAddLinksResponse::Batch(
+        handlers::bulk::add_links_batch(batch.links, batch.atomic, auth.user_id(), ids, store.inner().as_ref(), config)
+          .json_respond()
+      )
+    },
+    Err(r) => BulkAddLinksResponse::Legacy(
+      r
+        .transform(None)
+        .to_response()
+        .json_respond()
+    )
+  }
 }
 #[post("/add-link", data = "<links>")]
-pub fn post_add_link(links: Json<Vec<requests::NewLink>>, db: &State<Pool>, _rl: RateLimit, config: &State<Config>) -> (Status, Json<Response<Vec<successes::NewLinkResult>>>) {
+pub fn post_add_link(links: Json<requests::BulkNewLinks>, auth: Auth, store: &State<Store>, ids: &State<LinkIdGenerator>, rl: BulkRateLimit<'_>, config: &State<Config>) -> BulkAddLinksResponse {
   let links = links.into_inner();
 
-  handlers::bulk::add_links(links, db, config)
-    .json_respond()
+  match rl.charge(links.item_count()) {
+    Ok(()) => match links {
+      requests::BulkNewLinks::Links(links) => BulkAddLinksResponse::Legacy(
+        handlers::bulk::add_links(links, auth.user_id(), ids, store.inner().as_ref(), config)
+          .json_respond()
+      ),
+      requests::BulkNewLinks::Batch(batch) => BulkAddLinksResponse::Batch(
+        handlers::bulk::add_links_batch(batch.links, batch.atomic, auth.user_id(), ids, store.inner().as_ref(), config)
+          .json_respond()
+      )
+    },
+    Err(r) => BulkAddLinksResponse::Legacy(
+      r
+        .transform(None)
+        .to_response()
+        .json_respond()
+    )
+  }
+}
+
+#[delete("/delete-link", data = "<links>")]
+pub fn delete_delete_link(links: Json<Vec<requests::DeleteLink>>, auth: Auth, store: &State<Store>, rl: BulkRateLimit<'_>, config: &State<Config>) -> (Status, Json<Response<()>>) {
+  let links = links.into_inner();
+
+  match rl.charge(links.len()) {
+    Ok(()) => {
+      handlers::bulk::delete_links(links, auth.user_id(), store.inner().as_ref(), config)
+        .json_respond()
+    },
+    Err(r) => {
+      r
+        .to_response()
+        .json_respond()
+    }
+  }
+}
+
+#[post("/edit-link", data = "<links>")]
+pub fn post_edit_link(links: Json<Vec<requests::EditLink>>, auth: Auth, store: &State<Store>, rl: BulkRateLimit<'_>, config: &State<Config>) -> (Status, Json<Response<Vec<successes::EditLinkResult>>>) {
+  let links = links.into_inner();
+
+  match rl.charge(links.len()) {
+    Ok(()) => {
+      handlers::bulk::edit_links(links, auth.user_id(), store.inner().as_ref(), config)
+        .json_respond()
+    },
+    Err(r) => {
+      r
+        .transform(None)
+        .to_response()
+        .json_respond()
+    }
+  }
+}
+#[patch("/edit-link", data = "<links>")]
+pub fn patch_edit_link(links: Json<Vec<requests::EditLink>>, auth: Auth, store: &State<Store>, rl: BulkRateLimit<'_>, config: &State<Config>) -> (Status, Json<Response<Vec<successes::EditLinkResult>>>) {
+  let links = links.into_inner();
+
+  match rl.charge(links.len()) {
+    Ok(()) => {
+      handlers::bulk::edit_links(links, auth.user_id(), store.inner().as_ref(), config)
+        .json_respond()
+    },
+    Err(r) => {
+      r
+        .transform(None)
+        .to_response()
+        .json_respond()
+    }
+  }
 }
\ No newline at end of file
@@ -1,2 +1,4 @@
 pub mod root;
-pub mod bulk;
\ No newline at end of file
+pub mod bulk;
+pub mod users;
+pub mod admin;
\ No newline at end of file
@@ -1,16 +1,18 @@
-use rocket::{self, get, post, put, patch, delete, State, response::Redirect};
+use rocket::{self, get, post, put, patch, delete, State};
+use rocket::form::Form;
 use rocket::serde::{json::{Json}};
 use rocket::http::Status;
-use crate::fairings::database::Pool;
+use crate::store::{Store, LinksScope};
 use crate::{guards, responses::*, handlers};
 use crate::requests;
 use crate::config::Config;
+use crate::ids::LinkIdGenerator;
 
 #[get("/check-id/<link_id>")]
-pub fn get_check_id(link_id: String, db: &State<Pool>, _rl: guards::rate_limit::RateLimit) -> (Status, Json<Response<bool>>) {
+pub fn get_check_id(link_id: String, store: &State<Store>, _rl: guards::rate_limit::RateLimit) -> (Status, Json<Response<bool>>) {
   let res_data = ResponseData::<bool>::new();
 
-  match handlers::utils::check_id::<successes::NewLinkResult>(&link_id, db) {
+  match handlers::utils::check_id::<successes::NewLinkResult>(&link_id, store.inner().as_ref()) {
     Ok(r) => {
       res_data
         .success(
@@ -30,18 +32,58 @@ pub fn get_check_id(link_id: String, db: &State<Pool>, _rl: guards::rate_limit::
 }
 
 #[get("/l/<link_id>")]
-pub fn get_access_link(link_id: String, db: &State<Pool>, _rl: guards::rate_limit::RateLimit) -> Result<Redirect, (Status, Json<Response<()>>)> {
-  match handlers::root::access_link(link_id, db) {
-    Ok(redirect) => Ok(redirect),
+pub fn get_access_link(link_id: String, visitor: guards::visitor::Visitor, store: &State<Store>, _rl: guards::rate_limit::RateLimit) -> Result<AccessLinkResponse, (Status, Json<Response<()>>)> {
+  match handlers::root::access_link(link_id, None, &visitor, store.inner().as_ref()) {
+    Ok(response) => Ok(response),
+    Err(response) => Err(response.json_respond())
+  }
+}
+#[post("/l/<link_id>", data = "<unlock>")]
+pub fn post_access_link(link_id: String, unlock: Form<requests::UnlockLink>, visitor: guards::visitor::Visitor, store: &State<Store>, _rl: guards::rate_limit::RateLimit) -> Result<AccessLinkResponse, (Status, Json<Response<()>>)> {
+  match handlers::root::access_link(link_id, Some(&unlock.password), &visitor, store.inner().as_ref()) {
+    Ok(response) => Ok(response),
     Err(response) => Err(response.json_respond())
   }
 }
 
-#[get("/get-links")]
-pub fn get_get_links(db: &State<Pool>, _rl: guards::rate_limit::RateLimit, config: &State<Config>) -> (Status, Json<Response<Vec<successes::GetLink>>>) {
+#[get("/links/<link_id>/stats")]
+pub fn get_link_stats(link_id: String, control_key: guards::control_key::ControlKey, auth: guards::auth::Auth, store: &State<Store>, _rl: guards::rate_limit::RateLimit) -> (Status, Json<Response<successes::LinkStats>>) {
   let res_data = ResponseData::new();
 
-  match handlers::utils::get_links(db, config) {
+  match handlers::root::link_stats(&link_id, &control_key.0, auth.user_id(), store.inner().as_ref()) {
+    Ok(stats) => {
+      res_data.success(Status::Ok, Some(stats))
+        .to_response()
+        .json_respond()
+    },
+    Err(r) => {
+      r.transform::<successes::LinkStats>(None)
+        .to_response()
+        .json_respond()
+    }
+  }
+}
+
+#[get("/get-links?<query..>")]
+pub fn get_get_links(query: requests::GetLinks, auth: guards::auth::Auth, store: &State<Store>, _rl: guards::rate_limit::RateLimit, config: &State<Config>) -> (Status, Json<Response<successes::GetLinks>>) {
+  let res_data = ResponseData::new();
+
+  let scope = match auth.user_id() {
+    Some(user_id) => LinksScope::Owner(user_id),
+    None if config.public_link_listing => LinksScope::Public,
+    None => {
+      return res_data.error(
+        Status::Forbidden,
+        ResponseErrorType::GetLinksError,
+        String::from("Public listing of links is disabled! Provide your API key or control keys of your links to list them."),
+        None
+      )
+        .to_response()
+        .json_respond();
+    }
+  };
+
+  match handlers::utils::get_links(&scope, &query, store.inner().as_ref(), config) {
     Ok(links) => {
       res_data.success(Status::Ok, Some(links))
         .to_response()
@@ -55,27 +97,57 @@ pub fn get_get_links(db: &State<Pool>, _rl: guards::rate_limit::RateLimit, confi
     }
   }
 }
+#[post("/get-links?<query..>", data = "<links>")]
+pub fn post_get_links(query: requests::GetLinks, links: Json<Vec<requests::LinkCredentials>>, store: &State<Store>, rl: guards::rate_limit::BulkRateLimit<'_>, config: &State<Config>) -> (Status, Json<Response<successes::GetLinks>>) {
+  let res_data = ResponseData::new();
+  let links = links.into_inner();
+  let authorized = handlers::utils::check_credentials_limit(&links, config)
+    .and_then(|()| rl.charge(links.len()))
+    .and_then(|()| handlers::utils::authorize_links(&links, store.inner().as_ref()));
+
+  match authorized {
+    Ok(link_ids) => match handlers::utils::get_links(&LinksScope::Links(link_ids), &query, store.inner().as_ref(), config) {
+      Ok(links) => {
+        res_data.success(Status::Ok, Some(links))
+          .to_response()
+          .json_respond()
+      },
+      Err(r) => {
+        r
+          .transform(None)
+          .to_response()
+          .json_respond()
+      }
+    },
+    Err(r) => {
+      r
+        .transform(None)
+        .to_response()
+        .json_respond()
+    }
+  }
+}
 
 #[post("/add-link", data = "<link>")]
-pub fn post_add_link(link: Json<requests::NewLink>, db: &State<Pool>, _rl: guards::rate_limit::RateLimit, config: &State<Config>) -> (Status, Json<Response<successes::NewLinkResult>>) {
+pub fn post_add_link(link: Json<requests::NewLink>, auth: guards::auth::Auth, store: &State<Store>, ids: &State<LinkIdGenerator>, _rl: guards::rate_limit::RateLimit, config: &State<Config>) -> (Status, Json<Response<successes::NewLinkResult>>) {
   let link = link.into_inner();
 
-  handlers::root::add_link(&link, db, config)
+  handlers::root::add_link(&link, auth.user_id(), ids, store.inner().as_ref(), config)
 }
 #[put("/add-link", data = "<link>")]
-pub fn put_add_link(link: Json<requests::NewLink>, db: &State<Pool>, _rl: guards::rate_limit::RateLimit, config: &State<Config>) -> (Status, Json<Response<successes::NewLinkResult>>) {
+pub fn put_add_link(link: Json<requests::NewLink>, auth: guards::auth::Auth, store: &State<Store>, ids: &State<LinkIdGenerator>, _rl: guards::rate_limit::RateLimit, config: &State<Config>) -> (Status, Json<Response<successes::NewLinkResult>>) {
   let link = link.into_inner();
 
-  handlers::root::add_link(&link, db, config)
+  handlers::root::add_link(&link, auth.user_id(), ids, store.inner().as_ref(), config)
 }
 
 #[delete("/delete-link", data = "<link>")]
-pub fn delete_delete_link(link: Json<requests::DeleteLink>, db: &State<Pool>, _rl: guards::rate_limit::RateLimit) -> (Status, Json<Response<()>>) {
+pub fn delete_delete_link(link: Json<requests::DeleteLink>, auth: guards::auth::Auth, store: &State<Store>, _rl: guards::rate_limit::RateLimit) -> (Status, Json<Response<()>>) {
   let res_data = ResponseData::new();
   let link_id = link.link_id.clone();
   let control_key = link.control_key.clone();
 
-  match handlers::root::delete_link(&link_id, &control_key, db) {
+  match handlers::root::delete_link(&link_id, &control_key, auth.user_id(), store.inner().as_ref()) {
     Ok(()) => {
       res_data.success(Status::Ok, None)
         .to_response()
@@ -86,14 +158,11 @@ pub fn delete_delete_link(link: Json<requests::DeleteLink>, db: &State<Pool>, _r
 }
 
 #[post("/edit-link", data = "<link>")]
-pub fn post_edit_link(link: Json<requests::EditLink>, db: &State<Pool>, _rl: guards::rate_limit::RateLimit, config: &State<Config>) -> (Status, Json<Response<successes::EditLinkResult>>) {
+pub fn post_edit_link(link: Json<requests::EditLink>, auth: guards::auth::Auth, store: &State<Store>, _rl: guards::rate_limit::RateLimit, config: &State<Config>) -> (Status, Json<Response<successes::EditLinkResult>>) {
   let res_data = ResponseData::new();
-  let link_id = link.link_id.clone();
-  let control_key = link.control_key.clone();
-  let new_link_id = link.new_link_id.clone();
-  let new_target = link.target.clone();
+  let link = link.into_inner();
  
-  match handlers::root::edit_link(&link_id, &control_key, &new_link_id, &new_target, db, config) {
+  match handlers::root::edit_link(&link, auth.user_id(), store.inner().as_ref(), config) {
     Ok(r) => {
       res_data.success(
         Status::Ok,
@@ -110,14 +179,11 @@ pub fn post_edit_link(link: Json<requests::EditLink>, db: &State<Pool>, _rl: gua
   }
 }
 #[patch("/edit-link", data = "<link>")]
-pub fn patch_edit_link(link: Json<requests::EditLink>, db: &State<Pool>, _rl: guards::rate_limit::RateLimit, config: &State<Config>) -> (Status, Json<Response<successes::EditLinkResult>>) {
+pub fn patch_edit_link(link: Json<requests::EditLink>, auth: guards::auth::Auth, store: &State<Store>, _rl: guards::rate_limit::RateLimit, config: &State<Config>) -> (Status, Json<Response<successes::EditLinkResult>>) {
   let res_data = ResponseData::new();
-  let link_id = link.link_id.clone();
-  let control_key = link.control_key.clone();
-  let new_link_id = link.new_link_id.clone();
-  let new_target = link.target.clone();
+  let link = link.into_inner();
  
-  match handlers::root::edit_link(&link_id, &control_key, &new_link_id, &new_target, db, config) {
+  match handlers::root::edit_link(&link, auth.user_id(), store.inner().as_ref(), config) {
     Ok(r) => {
       res_data.success(
         Status::Ok,
@@ -132,4 +198,22 @@ pub fn patch_edit_link(link: Json<requests::EditLink>, db: &State<Pool>, _rl: gu
         .json_respond()
     }
   }
+}
+
+#[post("/rotate-control-key", data = "<link>")]
+pub fn post_rotate_control_key(link: Json<requests::RotateControlKey>, auth: guards::auth::Auth, store: &State<Store>, _rl: guards::rate_limit::RateLimit) -> (Status, Json<Response<successes::RotateControlKeyResult>>) {
+  let res_data = ResponseData::new();
+
+  match handlers::root::rotate_control_key(&link.link_id, &link.control_key, auth.user_id(), store.inner().as_ref()) {
+    Ok(r) => {
+      res_data.success(Status::Ok, Some(r))
+        .to_response()
+        .json_respond()
+    },
+    Err(r) => {
+      r.transform::<successes::RotateControlKeyResult>(None)
+        .to_response()
+        .json_respond()
+    }
+  }
 }
\ No newline at end of file
@@ -0,0 +1,60 @@
+use rocket::{post, delete, State};
+use rocket::serde::json::Json;
+use rocket::http::Status;
+use crate::guards::rate_limit::RateLimit;
+use crate::guards::auth::Auth;
+use crate::fairings::database::Pool;
+use crate::responses::*;
+use crate::requests;
+use crate::config::Config;
+use crate::handlers;
+
+#[post("/register", data = "<user>")]
+pub fn post_register(user: Json<requests::NewUser>, db: &State<Pool>, _rl: RateLimit, config: &State<Config>) -> (Status, Json<Response<successes::NewUserResult>>) {
+  let res_data = ResponseData::new();
+
+  match handlers::users::register(&user, db, config) {
+    Ok(r) => {
+      res_data.success(Status::Ok, Some(r))
+        .to_response()
+        .json_respond()
+    },
+    Err(r) => {
+      r.transform::<successes::NewUserResult>(None)
+        .to_response()
+        .json_respond()
+    }
+  }
+}
+
+#[post("/api-keys")]
+pub fn post_api_keys(auth: Auth, db: &State<Pool>, _rl: RateLimit) -> (Status, Json<Response<successes::NewApiKeyResult>>) {
+  let res_data = ResponseData::new();
+
+  match handlers::users::create_api_key(auth.user_id(), db) {
+    Ok(r) => {
+      res_data.success(Status::Ok, Some(r))
+        .to_response()
+        .json_respond()
+    },
+    Err(r) => {
+      r.transform::<successes::NewApiKeyResult>(None)
+        .to_response()
+        .json_respond()
+    }
+  }
+}
+
+#[delete("/api-keys", data = "<key>")]
+pub fn delete_api_keys(key: Json<requests::RevokeApiKey>, auth: Auth, db: &State<Pool>, _rl: RateLimit) -> (Status, Json<Response<()>>) {
+  let res_data = ResponseData::new();
+
+  match handlers::users::revoke_api_key(&key.key_id, auth.user_id(), db) {
+    Ok(()) => {
+      res_data.success(Status::Ok, None)
+        .to_response()
+        .json_respond()
+    },
+    Err(r) => r.to_response().json_respond()
+  }
+}
\ No newline at end of file
@@ -1,5 +1,14 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (key_id) {
+        key_id -> Varchar,
+        user_id -> Bigint,
+        key_hash -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     links (link_id) {
         link_id -> Varchar,
@@ -7,5 +16,43 @@ diesel::table! {
         control_key -> Varchar,
         added_at -> Timestamp,
         visit_count -> Integer,
+        expires_at -> Nullable<Timestamp>,
+        max_visits -> Nullable<Integer>,
+        expired_at -> Nullable<Timestamp>,
+        password -> Nullable<Varchar>,
+        owner_id -> Nullable<Bigint>,
+        disabled -> Bool,
+        target_host -> Nullable<Varchar>,
+        link_key -> Varchar,
+    }
+}
+
+diesel::table! {
+    users (user_id) {
+        user_id -> Bigint,
+        username -> Varchar,
+        created_at -> Timestamp,
     }
 }
+
+diesel::table! {
+    visits (visit_id) {
+        visit_id -> Bigint,
+        link_id -> Varchar,
+        visited_at -> Timestamp,
+        referrer -> Nullable<Text>,
+        user_agent -> Nullable<Text>,
+        client_ip -> Nullable<Varchar>,
+    }
+}
+
+diesel::joinable!(api_keys -> users (user_id));
+diesel::joinable!(links -> users (owner_id));
+diesel::joinable!(visits -> links (link_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
+    links,
+    users,
+    visits,
+);
@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use crate::models;
+use super::*;
+
+struct CacheEntry {
+  link: models::Link,
+  cached_at: Instant,
+  tick: u64
+}
+
+#[derive(Default)]
+struct CacheState {
+  entries: HashMap<String, CacheEntry>,
+  recency: BTreeMap<u64, String>,
+  tick: u64
+}
+
+impl CacheState {
+  fn remove(&mut self, link_id: &String) -> Option<CacheEntry> {
+    let entry = self.entries.remove(link_id)?;
+    self.recency.remove(&entry.tick);
+
+    Some(entry)
+  }
+
+  fn next_tick(&mut self) -> u64 {
+    self.tick += 1;
+    self.tick
+  }
+}
+
+pub struct LinkCache {
+  capacity: usize,
+  ttl: Duration,
+  state: Mutex<CacheState>,
+  hits: AtomicU64,
+  misses: AtomicU64
+}
+
+impl LinkCache {
+  pub fn new(capacity: usize, ttl: u64) -> Self {
+    LinkCache {
+      capacity: capacity,
+      ttl: Duration::from_secs(ttl),
+      state: Mutex::new(CacheState::default()),
+      hits: AtomicU64::new(0),
+      misses: AtomicU64::new(0)
+    }
+  }
+
+  pub fn enabled(&self) -> bool {
+    self.capacity > 0 && !self.ttl.is_zero()
+  }
+
+  pub fn get(&self, link_id: &String) -> Option<models::Link> {
+    let mut state = match self.state.lock() {
+      Ok(state) => state,
+      Err(_) => return None
+    };
+
+    let link = match state.remove(link_id) {
+      Some(entry) if entry.cached_at.elapsed() < self.ttl => {
+        let tick = state.next_tick();
+        let link = entry.link.clone();
+
+        state.recency.insert(tick, link_id.clone());
+        state.entries.insert(link_id.clone(), CacheEntry { tick: tick, ..entry });
+
+        Some(link)
+      },
+      _ => None
+    };
+
+    match link {
+      Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+      None => self.misses.fetch_add(1, Ordering::Relaxed)
+    };
+
+    link
+  }
+
+  pub fn insert(&self, link: &models::Link) {
+    if !self.enabled() {
+      return;
+    }
+
+    if let Ok(mut state) = self.state.lock() {
+      state.remove(&link.link_key);
+
+      while state.entries.len() >= self.capacity {
+        match state.recency.keys().next().cloned() {
+          Some(oldest) => {
+            if let Some(link_id) = state.recency.remove(&oldest) {
+              state.entries.remove(&link_id);
+            }
+          },
+          None => break
+        }
+      }
+
+      let tick = state.next_tick();
+
+      state.recency.insert(tick, link.link_key.clone());
+      state.entries.insert(link.link_key.clone(), CacheEntry {
+        link: link.clone(),
+        cached_at: Instant::now(),
+        tick: tick
+      });
+    }
+  }
+
+  pub fn invalidate(&self, link_id: &String) {
+    if let Ok(mut state) = self.state.lock() {
+      state.remove(link_id);
+    }
+  }
+
+  pub fn record_visit(&self, link_id: &String) {
+    if let Ok(mut state) = self.state.lock() {
+      if let Some(entry) = state.entries.get_mut(link_id) {
+        entry.link.visit_count += 1;
+      }
+    }
+  }
+
+  pub fn size(&self) -> usize {
+    match self.state.lock() {
+      Ok(state) => state.entries.len(),
+      Err(_) => 0
+    }
+  }
+
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+
+  pub fn ttl(&self) -> u64 {
+    self.ttl.as_secs()
+  }
+
+  pub fn hits(&self) -> u64 {
+    self.hits.load(Ordering::Relaxed)
+  }
+
+  pub fn misses(&self) -> u64 {
+    self.misses.load(Ordering::Relaxed)
+  }
+}
+
+pub struct CachedLinkStore {
+  inner: Store,
+  cache: Arc<LinkCache>
+}
+
+impl CachedLinkStore {
+  pub fn new(inner: Store, cache: Arc<LinkCache>) -> Self {
+    CachedLinkStore { inner: inner, cache: cache }
+  }
+}
+
+impl LinkStore for CachedLinkStore {
+  fn create(&self, link: &models::NewLink) -> Result<(), StoreError> {
+    self.inner.create(link)
+  }
+
+  fn create_batch(&self, links: &Vec<models::NewLink>, atomic: bool) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    self.inner.create_batch(links, atomic)
+  }
+
+  fn get(&self, link_id: &String) -> Result<Option<models::Link>, StoreError> {
+    if !self.cache.enabled() {
+      return self.inner.get(link_id);
+    }
+
+    match self.cache.get(link_id) {
+      Some(link) => Ok(Some(link)),
+      None => {
+        let link = self.inner.get(link_id)?;
+
+        if let Some(link) = &link {
+          self.cache.insert(link);
+        }
+
+        Ok(link)
+      }
+    }
+  }
+
+  fn update(&self, link_id: &String, changes: &models::LinkChanges) -> Result<(), StoreError> {
+    let result = self.inner.update(link_id, changes);
+
+    self.cache.invalidate(link_id);
+    if let Some(new_link_key) = &changes.link_key {
+      self.cache.invalidate(new_link_key);
+    }
+
+    result
+  }
+
+  fn delete(&self, link_id: &String) -> Result<(), StoreError> {
+    let result = self.inner.delete(link_id);
+
+    self.cache.invalidate(link_id);
+
+    result
+  }
+
+  fn update_batch(&self, changes: &Vec<(String, models::LinkChanges)>) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    let result = self.inner.update_batch(changes);
+
+    for (link_id, changes) in changes.iter() {
+      self.cache.invalidate(link_id);
+      if let Some(new_link_key) = &changes.link_key {
+        self.cache.invalidate(new_link_key);
+      }
+    }
+
+    result
+  }
+
+  fn delete_batch(&self, link_ids: &Vec<String>) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    let result = self.inner.delete_batch(link_ids);
+
+    for link_id in link_ids.iter() {
+      self.cache.invalidate(link_id);
+    }
+
+    result
+  }
+
+  fn list(&self, query: &ListQuery) -> Result<(Vec<models::Link>, i64), StoreError> {
+    self.inner.list(query)
+  }
+
+  fn exists(&self, link_id: &String) -> Result<bool, StoreError> {
+    self.inner.exists(link_id)
+  }
+
+  fn increment_visits(&self, link_id: &String) -> Result<bool, StoreError> {
+    let incremented = self.inner.increment_visits(link_id)?;
+
+    match incremented {
+      true => self.cache.record_visit(link_id),
+      false => self.cache.invalidate(link_id)
+    }
+
+    Ok(incremented)
+  }
+
+  fn record_visit(&self, visit: &models::NewVisit) -> Result<(), StoreError> {
+    self.inner.record_visit(visit)
+  }
+
+  fn visit_stats(&self, link_id: &String, top_referrers: i64) -> Result<VisitStats, StoreError> {
+    self.inner.visit_stats(link_id, top_referrers)
+  }
+}
\ No newline at end of file
@@ -0,0 +1,396 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::result::{Error, DatabaseErrorKind};
+use diesel::dsl::count_star;
+use diesel::sql_types::{BigInt, Date, Text};
+use crate::fairings::database::{Pool, PoolConnection, DbBackend, DbConnection};
+use crate::requests::{LinksSortBy, SortOrder};
+use crate::schema::{links, visits};
+use crate::models;
+use crate::utils;
+use super::*;
+
+#[cfg(any(feature = "mysql", feature = "sqlite"))]
+const DAILY_VISITS_QUERY: &str = "SELECT date(visited_at) AS visit_date, COUNT(*) AS visits FROM visits WHERE link_id = ? GROUP BY date(visited_at) ORDER BY date(visited_at)";
+#[cfg(feature = "postgres")]
+const DAILY_VISITS_QUERY: &str = "SELECT date(visited_at) AS visit_date, COUNT(*) AS visits FROM visits WHERE link_id = $1 GROUP BY date(visited_at) ORDER BY date(visited_at)";
+
+define_sql_function!(fn lower(x: Text) -> Text);
+
+#[derive(QueryableByName)]
+struct DailyVisitsRow {
+  #[diesel(sql_type = Date)]
+  visit_date: NaiveDate,
+  #[diesel(sql_type = BigInt)]
+  visits: i64
+}
+
+pub struct DatabaseLinkStore {
+  pool: Pool
+}
+
+impl DatabaseLinkStore {
+  pub fn new(pool: Pool) -> Self {
+    DatabaseLinkStore { pool: pool }
+  }
+
+  fn conn(&self) -> Result<PoolConnection, StoreError> {
+    self.pool.get()
+      .map_err(|_| StoreError::Database(String::from("Could not get database pool!")))
+  }
+
+  fn apply_batch<T, F>(&self, items: &Vec<T>, apply: F) -> Result<Vec<Result<(), StoreError>>, StoreError>
+  where
+    F: Fn(&mut DbConnection, &T) -> Result<(), StoreError>
+  {
+    let conn = &mut *self.conn()?;
+    let mut results: Vec<Result<(), StoreError>> = Vec::new();
+
+    let outcome = conn.transaction::<(), Error, _>(|conn| {
+      for item in items.iter() {
+        let result = apply(conn, item);
+        let failed = result.is_err();
+
+        results.push(result);
+        if failed {
+          return Err(Error::RollbackTransaction);
+        }
+      }
+
+      Ok(())
+    });
+
+    match outcome {
+      Ok(()) | Err(Error::RollbackTransaction) => Ok(results),
+      Err(e) => Err(StoreError::from(e))
+    }
+  }
+}
+
+impl From<Error> for StoreError {
+  fn from(e: Error) -> Self {
+    match e {
+      Error::NotFound => StoreError::NotFound,
+      Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => StoreError::Duplicate,
+      e => StoreError::Database(e.to_string())
+    }
+  }
+}
+
+fn filter_links<'a>(query: &ListQuery) -> links::BoxedQuery<'a, DbBackend> {
+  let mut links_query = links::table.into_boxed::<DbBackend>();
+
+  match &query.scope {
+    LinksScope::Public => (),
+    LinksScope::Links(link_ids) => {
+      links_query = links_query.filter(links::link_key.eq_any(link_ids.clone()));
+    },
+    LinksScope::Owner(owner_id) => {
+      links_query = links_query.filter(links::owner_id.eq(*owner_id));
+    }
+  }
+
+  if let Some(domain) = &query.domain {
+    let domain = domain.trim_matches('.').to_lowercase();
+
+    links_query = links_query.filter(
+      links::target_host.eq(domain.clone())
+        .or(links::target_host.like(format!("%.{}", utils::escape_like(&domain))).escape('\\'))
+    );
+  }
+  if let Some(search) = &query.search {
+    let pattern = format!("%{}%", utils::escape_like(&search.to_lowercase()));
+
+    links_query = links_query.filter(
+      lower(links::link_id).like(pattern.clone()).escape('\\')
+        .or(lower(links::target).like(pattern).escape('\\'))
+    );
+  }
+  if let Some(created_after) = query.created_after {
+    links_query = links_query.filter(links::added_at.gt(created_after));
+  }
+  if let Some(created_before) = query.created_before {
+    links_query = links_query.filter(links::added_at.lt(created_before));
+  }
+
+  links_query
+}
+
+impl LinkStore for DatabaseLinkStore {
+  fn create(&self, link: &models::NewLink) -> Result<(), StoreError> {
+    let conn = &mut *self.conn()?;
+
+    diesel::insert_into(links::table)
+      .values(link)
+      .execute(conn)?;
+
+    Ok(())
+  }
+
+  fn create_batch(&self, new_links: &Vec<models::NewLink>, atomic: bool) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    let conn = &mut *self.conn()?;
+    let mut results: Vec<Result<(), StoreError>> = Vec::new();
+
+    let outcome = conn.transaction::<(), Error, _>(|conn| {
+      for link in new_links.iter() {
+        let result = conn.transaction::<usize, Error, _>(|conn| {
+          diesel::insert_into(links::table)
+            .values(link)
+            .execute(conn)
+        });
+
+        results.push(result.map(|_| ()).map_err(StoreError::from));
+      }
+
+      match atomic && results.iter().any(|result| result.is_err()) {
+        true => Err(Error::RollbackTransaction),
+        false => Ok(())
+      }
+    });
+
+    match outcome {
+      Ok(()) | Err(Error::RollbackTransaction) => Ok(results),
+      Err(e) => Err(StoreError::from(e))
+    }
+  }
+
+  fn get(&self, link_id: &String) -> Result<Option<models::Link>, StoreError> {
+    let conn = &mut *self.conn()?;
+
+    Ok(
+      links::table
+        .filter(links::link_key.eq(link_id))
+        .first::<models::Link>(conn)
+        .optional()?
+    )
+  }
+
+  fn update(&self, link_id: &String, changes: &models::LinkChanges) -> Result<(), StoreError> {
+    let conn = &mut *self.conn()?;
+
+    match diesel::update(links::table.filter(links::link_key.eq(link_id)))
+      .set(changes)
+      .execute(conn)? {
+        0 => Err(StoreError::NotFound),
+        _ => Ok(())
+      }
+  }
+
+  fn delete(&self, link_id: &String) -> Result<(), StoreError> {
+    let conn = &mut *self.conn()?;
+
+    match diesel::delete(links::table.filter(links::link_key.eq(link_id)))
+      .execute(conn)? {
+        0 => Err(StoreError::NotFound),
+        _ => Ok(())
+      }
+  }
+
+  fn update_batch(&self, changes: &Vec<(String, models::LinkChanges)>) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    self.apply_batch(changes, |conn, (link_id, changes)| {
+      match diesel::update(links::table.filter(links::link_key.eq(link_id)))
+        .set(changes)
+        .execute(conn)? {
+          0 => Err(StoreError::NotFound),
+          _ => Ok(())
+        }
+    })
+  }
+
+  fn delete_batch(&self, link_ids: &Vec<String>) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    self.apply_batch(link_ids, |conn, link_id| {
+      match diesel::delete(links::table.filter(links::link_key.eq(link_id)))
+        .execute(conn)? {
+          0 => Err(StoreError::NotFound),
+          _ => Ok(())
+        }
+    })
+  }
+
+  fn list(&self, query: &ListQuery) -> Result<(Vec<models::Link>, i64), StoreError> {
+    let conn = &mut *self.conn()?;
+
+    let total = filter_links(query)
+      .count()
+      .get_result::<i64>(conn)?;
+
+    let mut links_query = filter_links(query);
+
+    links_query = match (query.sort_by, query.order) {
+      (LinksSortBy::AddedAt, SortOrder::Asc) => links_query.order((links::added_at.asc(), links::link_id.asc())),
+      (LinksSortBy::AddedAt, SortOrder::Desc) => links_query.order((links::added_at.desc(), links::link_id.desc())),
+      (LinksSortBy::VisitCount, SortOrder::Asc) => links_query.order((links::visit_count.asc(), links::link_id.asc())),
+      (LinksSortBy::VisitCount, SortOrder::Desc) => links_query.order((links::visit_count.desc(), links::link_id.desc()))
+    };
+
+    links_query = match (&query.cursor, query.order) {
+      (Some((CursorValue::AddedAt(added_at), link_id)), SortOrder::Asc) => links_query.filter(
+        links::added_at.gt(*added_at).or(links::added_at.eq(*added_at).and(links::link_id.gt(link_id.clone())))
+      ),
+      (Some((CursorValue::AddedAt(added_at), link_id)), SortOrder::Desc) => links_query.filter(
+        links::added_at.lt(*added_at).or(links::added_at.eq(*added_at).and(links::link_id.lt(link_id.clone())))
+      ),
+      (Some((CursorValue::VisitCount(visit_count), link_id)), SortOrder::Asc) => links_query.filter(
+        links::visit_count.gt(*visit_count).or(links::visit_count.eq(*visit_count).and(links::link_id.gt(link_id.clone())))
+      ),
+      (Some((CursorValue::VisitCount(visit_count), link_id)), SortOrder::Desc) => links_query.filter(
+        links::visit_count.lt(*visit_count).or(links::visit_count.eq(*visit_count).and(links::link_id.lt(link_id.clone())))
+      ),
+      (None, _) => match query.offset {
+        Some(offset) if offset > 0 => links_query.offset(offset),
+        _ => links_query
+      }
+    };
+
+    let links = links_query
+      .limit(query.limit)
+      .load::<models::Link>(conn)?;
+
+    Ok((links, total))
+  }
+
+  fn exists(&self, link_id: &String) -> Result<bool, StoreError> {
+    let conn = &mut *self.conn()?;
+
+    let count = links::table
+      .filter(links::link_key.eq(link_id))
+      .count()
+      .get_result::<i64>(conn)?;
+
+    Ok(count > 0)
+  }
+
+  fn increment_visits(&self, link_id: &String) -> Result<bool, StoreError> {
+    let conn = &mut *self.conn()?;
+
+    let updated = diesel::update(links::table.filter(links::link_key.eq(link_id)))
+      .filter(links::max_visits.is_null().or(links::visit_count.lt(links::max_visits.assume_not_null())))
+      .set(links::visit_count.eq(links::visit_count + 1))
+      .execute(conn)?;
+
+    Ok(updated > 0)
+  }
+
+  fn record_visit(&self, visit: &models::NewVisit) -> Result<(), StoreError> {
+    let conn = &mut *self.conn()?;
+
+    diesel::insert_into(visits::table)
+      .values(visit)
+      .execute(conn)?;
+
+    Ok(())
+  }
+
+  fn visit_stats(&self, link_id: &String, top_referrers: i64) -> Result<VisitStats, StoreError> {
+    let conn = &mut *self.conn()?;
+
+    let link_id = match links::table
+      .filter(links::link_key.eq(link_id))
+      .select(links::link_id)
+      .first::<String>(conn)
+      .optional()? {
+        Some(link_id) => link_id,
+        None => return Ok(VisitStats::default())
+      };
+
+    let total = visits::table
+      .filter(visits::link_id.eq(&link_id))
+      .count()
+      .get_result::<i64>(conn)?;
+
+    let daily = diesel::sql_query(DAILY_VISITS_QUERY)
+      .bind::<Text, _>(&link_id)
+      .load::<DailyVisitsRow>(conn)?
+      .into_iter()
+      .map(|row| (row.visit_date, row.visits))
+      .collect();
+
+    let top_referrers = visits::table
+      .filter(visits::link_id.eq(&link_id))
+      .group_by(visits::referrer)
+      .select((visits::referrer, count_star()))
+      .order_by((count_star().desc(), visits::referrer.asc()))
+      .limit(top_referrers)
+      .load::<(Option<String>, i64)>(conn)?;
+
+    Ok(VisitStats {
+      total: total,
+      daily: daily,
+      top_referrers: top_referrers
+    })
+  }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+  use chrono::NaiveDateTime;
+  use crate::fairings::database::test_pool;
+  use super::*;
+
+  fn new_link(link_id: &str) -> models::NewLink {
+    models::NewLink {
+      link_id: String::from(link_id),
+      target: String::from("https://example.com/"),
+      control_key: String::from("hash"),
+      expires_at: None,
+      max_visits: None,
+      password: None,
+      owner_id: None,
+      target_host: Some(String::from("example.com")),
+      link_key: String::from(link_id)
+    }
+  }
+
+  fn visit(store: &DatabaseLinkStore, link_id: &str, visited_at: &str, referrer: Option<&str>) {
+    let conn = &mut *store.conn().unwrap();
+
+    diesel::insert_into(visits::table)
+      .values((
+        visits::link_id.eq(link_id),
+        visits::visited_at.eq(NaiveDateTime::parse_from_str(visited_at, "%Y-%m-%d %H:%M:%S").unwrap()),
+        visits::referrer.eq(referrer)
+      ))
+      .execute(conn)
+      .unwrap();
+  }
+
+  fn date(date: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+  }
+
+  #[test]
+  fn visit_stats_aggregates_visits() {
+    let store = DatabaseLinkStore::new(test_pool());
+    let link_id = String::from("example");
+
+    store.create(&new_link("example")).unwrap();
+    store.create(&new_link("other")).unwrap();
+
+    visit(&store, "example", "2022-10-01 08:00:00", Some("https://a.example"));
+    visit(&store, "example", "2022-10-01 23:59:59", Some("https://a.example"));
+    visit(&store, "example", "2022-10-02 00:00:00", Some("https://b.example"));
+    visit(&store, "example", "2022-10-04 12:00:00", None);
+    visit(&store, "example", "2022-10-04 13:00:00", Some("https://a.example"));
+    visit(&store, "example", "2022-10-04 14:00:00", Some("https://c.example"));
+    visit(&store, "other", "2022-10-01 08:00:00", Some("https://a.example"));
+
+    let stats = store.visit_stats(&link_id, 2).unwrap();
+
+    assert_eq!(stats.total, 6);
+    assert_eq!(stats.daily, vec![(date("2022-10-01"), 2), (date("2022-10-02"), 1), (date("2022-10-04"), 3)]);
+    assert_eq!(stats.top_referrers, vec![(Some(String::from("https://a.example")), 3), (None, 1)]);
+  }
+
+  #[test]
+  fn visit_stats_of_unvisited_link_are_empty() {
+    let store = DatabaseLinkStore::new(test_pool());
+
+    store.create(&new_link("example")).unwrap();
+
+    let stats = store.visit_stats(&String::from("example"), 10).unwrap();
+
+    assert_eq!(stats.total, 0);
+    assert!(stats.daily.is_empty());
+    assert!(stats.top_referrers.is_empty());
+  }
+}
\ No newline at end of file
@@ -0,0 +1,374 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use crate::requests::{LinksSortBy, SortOrder};
+use crate::models;
+use crate::utils;
+use super::*;
+
+type VisitLog = Vec<(String, NaiveDateTime, Option<String>)>;
+
+#[derive(Default)]
+pub struct MemoryLinkStore {
+  links: Mutex<HashMap<String, models::Link>>,
+  visits: Mutex<VisitLog>
+}
+
+impl MemoryLinkStore {
+  pub fn new() -> Self {
+    MemoryLinkStore {
+      links: Mutex::new(HashMap::new()),
+      visits: Mutex::new(Vec::new())
+    }
+  }
+
+  fn links(&self) -> Result<MutexGuard<'_, HashMap<String, models::Link>>, StoreError> {
+    self.links.lock()
+      .map_err(|_| StoreError::Database(String::from("Could not acquire lock on links!")))
+  }
+
+  fn apply_batch<T, F>(&self, items: &Vec<T>, apply: F) -> Result<Vec<Result<(), StoreError>>, StoreError>
+  where
+    F: Fn(&mut HashMap<String, models::Link>, &mut VisitLog, &T) -> Result<(), StoreError>
+  {
+    let mut links = self.links()?;
+    let mut visits = self.visits_log()?;
+    let (links_before, visits_before) = (links.clone(), visits.clone());
+    let mut results: Vec<Result<(), StoreError>> = Vec::new();
+
+    for item in items.iter() {
+      let result = apply(&mut links, &mut visits, item);
+      let failed = result.is_err();
+
+      results.push(result);
+      if failed {
+        *links = links_before;
+        *visits = visits_before;
+        break;
+      }
+    }
+
+    Ok(results)
+  }
+
+  fn visits_log(&self) -> Result<MutexGuard<'_, VisitLog>, StoreError> {
+    self.visits.lock()
+      .map_err(|_| StoreError::Database(String::from("Could not acquire lock on visits!")))
+  }
+}
+
+fn to_link(link: &models::NewLink) -> models::Link {
+  models::Link {
+    link_id: link.link_id.clone(),
+    target: link.target.clone(),
+    control_key: link.control_key.clone(),
+    added_at: Utc::now().naive_utc(),
+    visit_count: 0,
+    expires_at: link.expires_at,
+    max_visits: link.max_visits,
+    expired_at: None,
+    password: link.password.clone(),
+    owner_id: link.owner_id,
+    disabled: false,
+    target_host: link.target_host.clone(),
+    link_key: link.link_key.clone()
+  }
+}
+
+fn matches_query(link: &models::Link, query: &ListQuery) -> bool {
+  let in_scope = match &query.scope {
+    LinksScope::Public => true,
+    LinksScope::Links(link_keys) => link_keys.contains(&link.link_key),
+    LinksScope::Owner(owner_id) => link.owner_id == Some(*owner_id)
+  };
+  let domain = match &query.domain {
+    Some(domain) => match &link.target_host {
+      Some(host) => utils::host_matches(host, domain),
+      None => false
+    },
+    None => true
+  };
+  let search = match &query.search {
+    Some(search) => {
+      let search = search.to_lowercase();
+
+      link.link_id.to_lowercase().contains(&search) || link.target.to_lowercase().contains(&search)
+    },
+    None => true
+  };
+  let created_after = match query.created_after {
+    Some(created_after) => link.added_at > created_after,
+    None => true
+  };
+  let created_before = match query.created_before {
+    Some(created_before) => link.added_at < created_before,
+    None => true
+  };
+
+  in_scope && domain && search && created_after && created_before
+}
+
+fn update_link(links: &mut HashMap<String, models::Link>, visits: &mut VisitLog, link_key: &String, changes: &models::LinkChanges) -> Result<(), StoreError> {
+  if let Some(new_link_key) = &changes.link_key {
+    if new_link_key != link_key && links.contains_key(new_link_key) {
+      return Err(StoreError::Duplicate);
+    }
+  }
+
+  let mut link = match links.remove(link_key) {
+    Some(link) => link,
+    None => return Err(StoreError::NotFound)
+  };
+
+  if let Some(new_link_id) = &changes.link_id {
+    for visit in visits.iter_mut().filter(|(visit_link_id, _, _)| *visit_link_id == link.link_id) {
+      visit.0 = new_link_id.clone();
+    }
+    link.link_id = new_link_id.clone();
+  }
+  if let Some(new_link_key) = &changes.link_key {
+    link.link_key = new_link_key.clone();
+  }
+  if let Some(target) = &changes.target {
+    link.target = target.clone();
+  }
+  if let Some(target_host) = &changes.target_host {
+    link.target_host = target_host.clone();
+  }
+  if let Some(control_key) = &changes.control_key {
+    link.control_key = control_key.clone();
+  }
+  if let Some(visit_count) = changes.visit_count {
+    link.visit_count = visit_count;
+  }
+  if let Some(expires_at) = changes.expires_at {
+    link.expires_at = expires_at;
+  }
+  if let Some(max_visits) = changes.max_visits {
+    link.max_visits = max_visits;
+  }
+  if let Some(disabled) = changes.disabled {
+    link.disabled = disabled;
+  }
+
+  links.insert(link.link_key.clone(), link);
+
+  Ok(())
+}
+
+fn delete_link(links: &mut HashMap<String, models::Link>, visits: &mut VisitLog, link_key: &String) -> Result<(), StoreError> {
+  match links.remove(link_key) {
+    Some(link) => {
+      visits.retain(|(visit_link_id, _, _)| *visit_link_id != link.link_id);
+      Ok(())
+    },
+    None => Err(StoreError::NotFound)
+  }
+}
+
+impl LinkStore for MemoryLinkStore {
+  fn create(&self, link: &models::NewLink) -> Result<(), StoreError> {
+    let mut links = self.links()?;
+
+    if links.contains_key(&link.link_key) {
+      return Err(StoreError::Duplicate);
+    }
+    links.insert(link.link_key.clone(), to_link(link));
+
+    Ok(())
+  }
+
+  fn create_batch(&self, new_links: &Vec<models::NewLink>, atomic: bool) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    let mut links = self.links()?;
+    let mut results: Vec<Result<(), StoreError>> = Vec::new();
+    let mut created: Vec<String> = Vec::new();
+
+    for link in new_links.iter() {
+      if links.contains_key(&link.link_key) {
+        results.push(Err(StoreError::Duplicate));
+      } else {
+        links.insert(link.link_key.clone(), to_link(link));
+        created.push(link.link_key.clone());
+        results.push(Ok(()));
+      }
+    }
+
+    if atomic && results.iter().any(|result| result.is_err()) {
+      for link_key in created.iter() {
+        links.remove(link_key);
+      }
+    }
+
+    Ok(results)
+  }
+
+  fn get(&self, link_id: &String) -> Result<Option<models::Link>, StoreError> {
+    Ok(self.links()?.get(link_id).cloned())
+  }
+
+  fn update(&self, link_id: &String, changes: &models::LinkChanges) -> Result<(), StoreError> {
+    let mut links = self.links()?;
+    let mut visits = self.visits_log()?;
+
+    update_link(&mut links, &mut visits, link_id, changes)
+  }
+
+  fn delete(&self, link_id: &String) -> Result<(), StoreError> {
+    let mut links = self.links()?;
+    let mut visits = self.visits_log()?;
+
+    delete_link(&mut links, &mut visits, link_id)
+  }
+
+  fn update_batch(&self, changes: &Vec<(String, models::LinkChanges)>) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    self.apply_batch(changes, |links, visits, (link_id, changes)| update_link(links, visits, link_id, changes))
+  }
+
+  fn delete_batch(&self, link_ids: &Vec<String>) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    self.apply_batch(link_ids, delete_link)
+  }
+
+  fn list(&self, query: &ListQuery) -> Result<(Vec<models::Link>, i64), StoreError> {
+    let mut links = self.links()?
+      .values()
+      .filter(|link| matches_query(link, query))
+      .cloned()
+      .collect::<Vec<models::Link>>();
+    let total = links.len() as i64;
+
+    links.sort_by(|a, b| {
+      let ordering = match query.sort_by {
+        LinksSortBy::AddedAt => a.added_at.cmp(&b.added_at),
+        LinksSortBy::VisitCount => a.visit_count.cmp(&b.visit_count)
+      }
+        .then_with(|| a.link_id.cmp(&b.link_id));
+
+      match query.order {
+        SortOrder::Asc => ordering,
+        SortOrder::Desc => ordering.reverse()
+      }
+    });
+
+    let links = match &query.cursor {
+      Some((value, link_id)) => links.into_iter()
+        .filter(|link| {
+          let ordering = match value {
+            CursorValue::AddedAt(added_at) => link.added_at.cmp(added_at),
+            CursorValue::VisitCount(visit_count) => link.visit_count.cmp(visit_count)
+          }
+            .then_with(|| link.link_id.cmp(link_id));
+
+          match query.order {
+            SortOrder::Asc => ordering.is_gt(),
+            SortOrder::Desc => ordering.is_lt()
+          }
+        })
+        .collect::<Vec<models::Link>>(),
+      None => links.into_iter()
+        .skip(query.offset.unwrap_or(0).max(0) as usize)
+        .collect::<Vec<models::Link>>()
+    };
+
+    Ok((links.into_iter().take(query.limit.max(0) as usize).collect(), total))
+  }
+
+  fn exists(&self, link_id: &String) -> Result<bool, StoreError> {
+    Ok(self.links()?.contains_key(link_id))
+  }
+
+  fn increment_visits(&self, link_id: &String) -> Result<bool, StoreError> {
+    match self.links()?.get_mut(link_id) {
+      Some(link) => match link.max_visits {
+        Some(max_visits) if link.visit_count >= max_visits => Ok(false),
+        _ => {
+          link.visit_count += 1;
+          Ok(true)
+        }
+      },
+      None => Ok(false)
+    }
+  }
+
+  fn record_visit(&self, visit: &models::NewVisit) -> Result<(), StoreError> {
+    self.visits_log()?.push((visit.link_id.clone(), Utc::now().naive_utc(), visit.referrer.clone()));
+
+    Ok(())
+  }
+
+  fn visit_stats(&self, link_id: &String, top_referrers: i64) -> Result<VisitStats, StoreError> {
+    let mut stats = VisitStats::default();
+    let mut daily: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+    let mut referrers: HashMap<Option<String>, i64> = HashMap::new();
+    let link_id = match self.links()?.get(link_id) {
+      Some(link) => link.link_id.clone(),
+      None => return Ok(stats)
+    };
+
+    for (_, visited_at, referrer) in self.visits_log()?.iter().filter(|(visit_link_id, _, _)| *visit_link_id == link_id) {
+      stats.total += 1;
+      *daily.entry(visited_at.date()).or_insert(0) += 1;
+      *referrers.entry(referrer.clone()).or_insert(0) += 1;
+    }
+
+    stats.daily = daily.into_iter().collect();
+    stats.top_referrers = referrers.into_iter().collect();
+    stats.top_referrers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    stats.top_referrers.truncate(top_referrers.max(0) as usize);
+
+    Ok(stats)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn new_link(link_id: &str) -> models::NewLink {
+    models::NewLink {
+      link_id: String::from(link_id),
+      target: String::from("https://example.com/"),
+      control_key: String::from("hash"),
+      expires_at: None,
+      max_visits: None,
+      password: None,
+      owner_id: None,
+      target_host: Some(String::from("example.com")),
+      link_key: String::from(link_id)
+    }
+  }
+
+  fn visit(store: &MemoryLinkStore, link_id: &str, visited_at: &str, referrer: Option<&str>) {
+    store.visits_log().unwrap().push((
+      String::from(link_id),
+      NaiveDateTime::parse_from_str(visited_at, "%Y-%m-%d %H:%M:%S").unwrap(),
+      referrer.map(String::from)
+    ));
+  }
+
+  fn date(date: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+  }
+
+  #[test]
+  fn visit_stats_aggregates_visits() {
+    let store = MemoryLinkStore::new();
+    let link_id = String::from("example");
+
+    store.create(&new_link("example")).unwrap();
+    store.create(&new_link("other")).unwrap();
+
+    visit(&store, "example", "2022-10-01 08:00:00", Some("https://a.example"));
+    visit(&store, "example", "2022-10-01 23:59:59", Some("https://a.example"));
+    visit(&store, "example", "2022-10-02 00:00:00", Some("https://b.example"));
+    visit(&store, "example", "2022-10-04 12:00:00", None);
+    visit(&store, "example", "2022-10-04 13:00:00", Some("https://a.example"));
+    visit(&store, "example", "2022-10-04 14:00:00", Some("https://c.example"));
+    visit(&store, "other", "2022-10-01 08:00:00", Some("https://a.example"));
+
+    let stats = store.visit_stats(&link_id, 2).unwrap();
+
+    assert_eq!(stats.total, 6);
+    assert_eq!(stats.daily, vec![(date("2022-10-01"), 2), (date("2022-10-02"), 1), (date("2022-10-04"), 3)]);
+    assert_eq!(stats.top_referrers, vec![(Some(String::from("https://a.example")), 3), (None, 1)]);
+  }
+}
\ No newline at end of file
@@ -0,0 +1,66 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use crate::models;
+use crate::requests::{LinksSortBy, SortOrder};
+
+pub mod database;
+pub mod memory;
+pub mod cache;
+pub mod normalized;
+
+#[derive(Debug, Clone)]
+pub enum StoreError {
+  NotFound,
+  Duplicate,
+  Database(String)
+}
+
+#[derive(Debug, Clone)]
+pub enum LinksScope {
+  Public,
+  Links(Vec<String>),
+  Owner(i64)
+}
+
+#[derive(Debug, Clone)]
+pub enum CursorValue {
+  AddedAt(NaiveDateTime),
+  VisitCount(i32)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VisitStats {
+  pub total: i64,
+  pub daily: Vec<(NaiveDate, i64)>,
+  pub top_referrers: Vec<(Option<String>, i64)>
+}
+
+#[derive(Clone)]
+pub struct ListQuery {
+  pub scope: LinksScope,
+  pub domain: Option<String>,
+  pub search: Option<String>,
+  pub created_after: Option<NaiveDateTime>,
+  pub created_before: Option<NaiveDateTime>,
+  pub sort_by: LinksSortBy,
+  pub order: SortOrder,
+  pub cursor: Option<(CursorValue, String)>,
+  pub offset: Option<i64>,
+  pub limit: i64
+}
+
+pub trait LinkStore: Send + Sync {
+  fn create(&self, link: &models::NewLink) -> Result<(), StoreError>;
+  fn create_batch(&self, links: &Vec<models::NewLink>, atomic: bool) -> Result<Vec<Result<(), StoreError>>, StoreError>;
+  fn get(&self, link_id: &String) -> Result<Option<models::Link>, StoreError>;
+  fn update(&self, link_id: &String, changes: &models::LinkChanges) -> Result<(), StoreError>;
+  fn delete(&self, link_id: &String) -> Result<(), StoreError>;
+  fn update_batch(&self, changes: &Vec<(String, models::LinkChanges)>) -> Result<Vec<Result<(), StoreError>>, StoreError>;
+  fn delete_batch(&self, link_ids: &Vec<String>) -> Result<Vec<Result<(), StoreError>>, StoreError>;
+  fn list(&self, query: &ListQuery) -> Result<(Vec<models::Link>, i64), StoreError>;
+  fn exists(&self, link_id: &String) -> Result<bool, StoreError>;
+  fn increment_visits(&self, link_id: &String) -> Result<bool, StoreError>;
+  fn record_visit(&self, visit: &models::NewVisit) -> Result<(), StoreError>;
+  fn visit_stats(&self, link_id: &String, top_referrers: i64) -> Result<VisitStats, StoreError>;
+}
+
+pub type Store = Box<dyn LinkStore>;
\ No newline at end of file
@@ -0,0 +1,200 @@
+use crate::models;
+use crate::utils;
+use super::*;
+
+pub struct NormalizedLinkStore {
+  inner: Store,
+  case_insensitive: bool
+}
+
+impl NormalizedLinkStore {
+  pub fn new(inner: Store, case_insensitive: bool) -> Self {
+    NormalizedLinkStore { inner: inner, case_insensitive: case_insensitive }
+  }
+
+  fn key(&self, link_id: &String) -> String {
+    utils::link_key(link_id, self.case_insensitive)
+  }
+
+  fn normalize_link(&self, link: &models::NewLink) -> models::NewLink {
+    models::NewLink {
+      link_id: utils::normalize_id(&link.link_id),
+      link_key: self.key(&link.link_id),
+      ..link.clone()
+    }
+  }
+
+  fn normalize_changes(&self, changes: &models::LinkChanges) -> models::LinkChanges {
+    models::LinkChanges {
+      link_id: changes.link_id.as_ref().map(|new_link_id| utils::normalize_id(new_link_id)),
+      link_key: changes.link_id.as_ref().map(|new_link_id| self.key(new_link_id)),
+      ..changes.clone()
+    }
+  }
+}
+
+impl LinkStore for NormalizedLinkStore {
+  fn create(&self, link: &models::NewLink) -> Result<(), StoreError> {
+    self.inner.create(&self.normalize_link(link))
+  }
+
+  fn create_batch(&self, links: &Vec<models::NewLink>, atomic: bool) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    let links: Vec<models::NewLink> = links.iter()
+      .map(|link| self.normalize_link(link))
+      .collect();
+
+    self.inner.create_batch(&links, atomic)
+  }
+
+  fn get(&self, link_id: &String) -> Result<Option<models::Link>, StoreError> {
+    self.inner.get(&self.key(link_id))
+  }
+
+  fn update(&self, link_id: &String, changes: &models::LinkChanges) -> Result<(), StoreError> {
+    self.inner.update(&self.key(link_id), &self.normalize_changes(changes))
+  }
+
+  fn delete(&self, link_id: &String) -> Result<(), StoreError> {
+    self.inner.delete(&self.key(link_id))
+  }
+
+  fn update_batch(&self, changes: &Vec<(String, models::LinkChanges)>) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    let changes: Vec<(String, models::LinkChanges)> = changes.iter()
+      .map(|(link_id, changes)| (self.key(link_id), self.normalize_changes(changes)))
+      .collect();
+
+    self.inner.update_batch(&changes)
+  }
+
+  fn delete_batch(&self, link_ids: &Vec<String>) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+    let link_ids: Vec<String> = link_ids.iter()
+      .map(|link_id| self.key(link_id))
+      .collect();
+
+    self.inner.delete_batch(&link_ids)
+  }
+
+  fn list(&self, query: &ListQuery) -> Result<(Vec<models::Link>, i64), StoreError> {
+    let query = match &query.scope {
+      LinksScope::Links(link_ids) => ListQuery {
+        scope: LinksScope::Links(link_ids.iter().map(|link_id| self.key(link_id)).collect()),
+        ..query.clone()
+      },
+      _ => query.clone()
+    };
+
+    self.inner.list(&query)
+  }
+
+  fn exists(&self, link_id: &String) -> Result<bool, StoreError> {
+    self.inner.exists(&self.key(link_id))
+  }
+
+  fn increment_visits(&self, link_id: &String) -> Result<bool, StoreError> {
+    self.inner.increment_visits(&self.key(link_id))
+  }
+
+  fn record_visit(&self, visit: &models::NewVisit) -> Result<(), StoreError> {
+    self.inner.record_visit(visit)
+  }
+
+  fn visit_stats(&self, link_id: &String, top_referrers: i64) -> Result<VisitStats, StoreError> {
+    self.inner.visit_stats(&self.key(link_id), top_referrers)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::requests::{LinksSortBy, SortOrder};
+  use crate::store::memory::MemoryLinkStore;
+
+  fn new_link(link_id: &str) -> models::NewLink {
+    models::NewLink {
+      link_id: String::from(link_id),
+      target: String::from("https://Example.com/Path"),
+      control_key: String::from("hash"),
+      expires_at: None,
+      max_visits: None,
+      password: None,
+      owner_id: None,
+      target_host: Some(String::from("example.com")),
+      link_key: String::from(link_id)
+    }
+  }
+
+  fn query(scope: LinksScope, search: Option<&str>) -> ListQuery {
+    ListQuery {
+      scope: scope,
+      domain: None,
+      search: search.map(String::from),
+      created_after: None,
+      created_before: None,
+      sort_by: LinksSortBy::AddedAt,
+      order: SortOrder::Desc,
+      cursor: None,
+      offset: None,
+      limit: 10
+    }
+  }
+
+  fn link_ids(store: &dyn LinkStore, query: &ListQuery) -> Vec<String> {
+    let mut link_ids: Vec<String> = store.list(query).unwrap().0.into_iter()
+      .map(|link| link.link_id)
+      .collect();
+
+    link_ids.sort();
+    link_ids
+  }
+
+  fn check_case_insensitive_lookups(inner: Store) {
+    let store = NormalizedLinkStore::new(inner, true);
+    let id = |link_id: &str| String::from(link_id);
+
+    store.create(&new_link("MyLink")).unwrap();
+
+    assert_eq!(store.get(&id("MYLINK")).unwrap().unwrap().link_id, "MyLink");
+    assert!(store.exists(&id("mylink")).unwrap());
+    assert!(matches!(store.create(&new_link("mylink")), Err(StoreError::Duplicate)));
+    assert!(matches!(store.create_batch(&vec![new_link("Other"), new_link("MYLINK")], false).unwrap()[1], Err(StoreError::Duplicate)));
+    assert!(matches!(store.update(&id("other"), &models::LinkChanges { link_id: Some(id("MYLINK")), ..Default::default() }), Err(StoreError::Duplicate)));
+
+    store.update(&id("mylink"), &models::LinkChanges { link_id: Some(id("MYLINK")), ..Default::default() }).unwrap();
+
+    assert_eq!(store.get(&id("MyLink")).unwrap().unwrap().link_id, "MYLINK");
+    assert_eq!(link_ids(&store, &query(LinksScope::Links(vec![id("mylink"), id("OTHER")]), None)), vec!["MYLINK", "Other"]);
+    assert_eq!(link_ids(&store, &query(LinksScope::Public, Some("mYl"))), vec!["MYLINK"]);
+    assert_eq!(link_ids(&store, &query(LinksScope::Public, Some("example.COM/path"))), vec!["MYLINK", "Other"]);
+
+    store.delete(&id("Mylink")).unwrap();
+
+    assert!(!store.exists(&id("MYLINK")).unwrap());
+  }
+
+  fn check_case_sensitive_lookups(inner: Store) {
+    let store = NormalizedLinkStore::new(inner, false);
+
+    store.create(&new_link("MyLink")).unwrap();
+    store.create(&new_link("mylink")).unwrap();
+
+    assert_eq!(store.get(&String::from("MyLink")).unwrap().unwrap().link_id, "MyLink");
+    assert_eq!(store.get(&String::from("mylink")).unwrap().unwrap().link_id, "mylink");
+    assert!(store.get(&String::from("MYLINK")).unwrap().is_none());
+  }
+
+  #[test]
+  fn memory_store_looks_up_links_by_key() {
+    check_case_insensitive_lookups(Box::new(MemoryLinkStore::new()));
+    check_case_sensitive_lookups(Box::new(MemoryLinkStore::new()));
+  }
+
+  #[cfg(feature = "sqlite")]
+  #[test]
+  fn database_store_looks_up_links_by_key() {
+    use crate::fairings::database::test_pool;
+    use crate::store::database::DatabaseLinkStore;
+
+    check_case_insensitive_lookups(Box::new(DatabaseLinkStore::new(test_pool())));
+    check_case_sensitive_lookups(Box::new(DatabaseLinkStore::new(test_pool())));
+  }
+}
\ No newline at end of file
@@ -1,3 +1,10 @@
+use std::net::{IpAddr, Ipv4Addr};
+use serde::{Deserialize, Deserializer};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use unicode_normalization::UnicodeNormalization;
+use url::Url;
+use sha2::{Digest, Sha256};
+
 #[derive(Debug, Clone)]
 pub enum Either<A, B> {
   Left(A),
@@ -43,4 +50,147 @@ impl<A, B> Either<A, B> {
       Either::Right(b) => b
     }
   }
+}
+
+pub fn anonymize_ip(ip: IpAddr) -> String {
+  match ip {
+    IpAddr::V4(ip) => {
+      let octets = ip.octets();
+
+      format!("{}.{}.{}.0", octets[0], octets[1], octets[2])
+    },
+    IpAddr::V6(ip) => {
+      let segments = ip.segments();
+
+      format!("{:x}:{:x}:{:x}::", segments[0], segments[1], segments[2])
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Cidr {
+  network: IpAddr,
+  prefix: u32
+}
+
+impl Cidr {
+  pub fn parse(value: &str) -> Option<Cidr> {
+    let (address, prefix) = match value.split_once('/') {
+      Some((address, prefix)) => (address, Some(prefix)),
+      None => (value, None)
+    };
+    let network = address.trim().parse::<IpAddr>().ok()?;
+    let max_prefix = match network {
+      IpAddr::V4(_) => 32,
+      IpAddr::V6(_) => 128
+    };
+    let prefix = match prefix {
+      Some(prefix) => prefix.trim().parse::<u32>().ok().filter(|prefix| *prefix <= max_prefix)?,
+      None => max_prefix
+    };
+
+    Some(Cidr { network: network, prefix: prefix })
+  }
+
+  pub fn contains(&self, ip: &IpAddr) -> bool {
+    let ip = match ip {
+      IpAddr::V6(ip) => match ip.segments() {
+        [0, 0, 0, 0, 0, 0xffff, high, low] => IpAddr::V4(Ipv4Addr::from(((high as u32) << 16) | low as u32)),
+        _ => IpAddr::V6(*ip)
+      },
+      ip => *ip
+    };
+
+    match (self.network, ip) {
+      (IpAddr::V4(network), IpAddr::V4(ip)) => {
+        let mask = u32::MAX.checked_shl(32 - self.prefix).unwrap_or(0);
+
+        u32::from(network) & mask == u32::from(ip) & mask
+      },
+      (IpAddr::V6(network), IpAddr::V6(ip)) => {
+        let mask = u128::MAX.checked_shl(128 - self.prefix).unwrap_or(0);
+
+        u128::from(network) & mask == u128::from(ip) & mask
+      },
+      _ => false
+    }
+  }
+}
+
+pub fn target_host(target: &str) -> Option<String> {
+  Url::parse(target).ok()?
+    .host_str()
+    .map(|host| host.to_lowercase())
+}
+
+pub fn host_matches(host: &str, domain: &str) -> bool {
+  let domain = domain.trim_matches('.').to_lowercase();
+
+  host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+pub fn normalize_id(link_id: &str) -> String {
+  link_id.nfc().collect()
+}
+
+pub fn link_key(link_id: &str, case_insensitive: bool) -> String {
+  match case_insensitive {
+    true => normalize_id(link_id).to_lowercase(),
+    false => normalize_id(link_id)
+  }
+}
+
+pub fn deserialize_nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+  T: Deserialize<'de>,
+  D: Deserializer<'de>
+{
+  Option::<T>::deserialize(deserializer).map(Some)
+}
+
+pub fn parse_datetime(date: &str) -> Option<NaiveDateTime> {
+  if let Ok(date) = DateTime::parse_from_rfc3339(date) {
+    return Some(date.naive_utc());
+  }
+  if let Ok(date) = NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S%.f") {
+    return Some(date);
+  }
+  match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+    Ok(date) => date.and_hms_opt(0, 0, 0),
+    Err(_) => None
+  }
+}
+
+pub fn escape_like(value: &str) -> String {
+  let mut escaped = String::with_capacity(value.len());
+
+  for c in value.chars() {
+    if c == '\\' || c == '%' || c == '_' {
+      escaped.push('\\');
+    }
+    escaped.push(c);
+  }
+
+  escaped
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+  bytes.iter()
+    .map(|b| format!("{:02x}", b))
+    .collect()
+}
+
+pub fn hash_api_key(secret: &str) -> String {
+  encode_hex(&Sha256::digest(secret.as_bytes()))
+}
+
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+  if !hex.len().is_multiple_of(2) {
+    return None;
+  }
+
+  (0..hex.len())
+    .step_by(2)
+    .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+    .collect()
 }
\ No newline at end of file